@@ -1,14 +1,16 @@
 pub mod color_library;
-mod scene_loader;
+pub mod scene_loader;
+pub mod text_field;
 
 use bevy::prelude::*;
 use color_library::ColorLibraryPlugin;
 use scene_loader::SceneLoaderPlugin;
+use text_field::TextFieldPlugin;
 
 pub struct GameSystemsPlugin;
 
 impl Plugin for GameSystemsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((ColorLibraryPlugin, SceneLoaderPlugin));
+        app.add_plugins((ColorLibraryPlugin, SceneLoaderPlugin, TextFieldPlugin));
     }
 }
@@ -1,35 +1,88 @@
+pub mod block_material;
+pub mod color_harmony;
+mod color_hotbar;
+pub mod color_picker;
 mod color_selector;
+mod palette_editor;
+pub mod palette_formats;
+pub mod palette_storage;
+pub mod quantize;
+pub mod recent_colors;
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::player::editor_modes::EditorMode;
+use self::block_material::{BlockMaterial, MaterialProperty};
+use crate::world::chunk::Chunk;
 
-use self::color_selector::ColorSelectorPlugin;
+use self::{
+    color_harmony::color_to_oklab, color_hotbar::ColorHotbarPlugin,
+    color_picker::ColorPickerPlugin, color_selector::ColorSelectorPlugin,
+    palette_editor::PaletteEditorPlugin, palette_formats::PaletteFormatsPlugin,
+    palette_storage::PaletteStoragePlugin, quantize::QuantizePlugin,
+    recent_colors::RecentColorsPlugin,
+};
 
 pub struct ColorLibraryPlugin;
 
 impl Plugin for ColorLibraryPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(ColorSelectorPlugin)
-            .add_event::<OnColorClicked>()
-            .insert_resource(ColorLibrary::with_default_colors())
-            .add_systems(
-                Update,
-                switch_to_clicked_color.run_if(in_state(EditorMode::Build)),
-            );
+        app.add_plugins((
+            ColorSelectorPlugin,
+            ColorPickerPlugin,
+            PaletteEditorPlugin,
+            PaletteStoragePlugin,
+            PaletteFormatsPlugin,
+            ColorHotbarPlugin,
+            RecentColorsPlugin,
+            QuantizePlugin,
+        ))
+        .add_event::<OnColorClicked>()
+        .insert_resource(ColorLibrary::with_default_colors())
+        .add_systems(Update, switch_to_clicked_color);
     }
 }
 
-#[derive(Resource)]
+/// A named palette of colors, one of them is selected for building.
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ColorLibrary {
+    name: String,
     selected_index: usize,
-    colors: Vec<Color>,
+    colors: Vec<PaletteColor>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PaletteColor {
+    pub color: Color,
+    pub name: String,
+    /// Palettes saved before materials existed use the default material.
+    #[serde(default)]
+    pub material: BlockMaterial,
+}
+
+impl PaletteColor {
+    pub fn new(color: Color) -> Self {
+        Self::with_name(color, String::new())
+    }
+
+    pub fn with_name(color: Color, name: impl Into<String>) -> Self {
+        Self {
+            color,
+            name: name.into(),
+            material: BlockMaterial::default(),
+        }
+    }
 }
 
 impl ColorLibrary {
+    const DEFAULT_NAME: &'static str = "default";
+    /// Blocks store their palette index in 16 bits, so a palette can't hold more colors than this.
+    pub const MAX_COLORS: usize = u16::MAX as usize + 1;
+
     #[allow(unused)]
     pub fn empty() -> Self {
         Self {
+            name: Self::DEFAULT_NAME.to_string(),
             colors: Vec::new(),
             selected_index: 0,
         }
@@ -37,18 +90,127 @@ impl ColorLibrary {
 
     pub fn with_default_colors() -> Self {
         Self {
-            colors: vec![Color::LIME_GREEN, Color::CYAN, Color::GRAY],
+            name: Self::DEFAULT_NAME.to_string(),
+            colors: [Color::LIME_GREEN, Color::CYAN, Color::GRAY]
+                .into_iter()
+                .map(PaletteColor::new)
+                .collect(),
             selected_index: 0,
         }
     }
 
-    #[allow(unused)]
-    pub fn add_color(&mut self, color: Color) {
-        self.colors.push(color);
+    pub fn from_colors(name: impl Into<String>, colors: Vec<PaletteColor>) -> Self {
+        Self {
+            name: name.into(),
+            colors,
+            selected_index: 0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name = name.into();
+    }
+
+    /// Adds the color to the end of the palette, returns false if the palette is full.
+    pub fn add_color(&mut self, color: Color) -> bool {
+        if self.count() >= Self::MAX_COLORS {
+            return false;
+        }
+
+        self.colors.push(PaletteColor::new(color));
+        true
+    }
+
+    /// Removes the color at the given index, the selection stays on the same color if it still exists.
+    pub fn remove_color(&mut self, index: usize) -> Option<PaletteColor> {
+        if index >= self.count() {
+            return None;
+        }
+
+        let removed = self.colors.remove(index);
+
+        if index < self.selected_index || self.selected_index >= self.count() {
+            self.selected_index = self.selected_index.saturating_sub(1);
+        }
+
+        Some(removed)
+    }
+
+    /// Moves the color at `from` to `to`, the selection follows the color it was on.
+    pub fn move_color(&mut self, from: usize, to: usize) {
+        if from >= self.count() || to >= self.count() {
+            return;
+        }
+
+        let moved = self.colors.remove(from);
+        self.colors.insert(to, moved);
+
+        self.selected_index = moved_index(self.selected_index, from, to);
+    }
+
+    pub fn rename_color(&mut self, index: usize, name: impl Into<String>) {
+        if let Some(color) = self.colors.get_mut(index) {
+            color.name = name.into();
+        }
+    }
+
+    pub fn color_name(&self, index: usize) -> Option<&str> {
+        self.colors.get(index).map(|color| color.name.as_str())
+    }
+
+    pub fn color_at(&self, index: usize) -> Option<Color> {
+        self.colors.get(index).map(|color| color.color)
+    }
+
+    /// Changes the color of an entry, every block using this entry changes with it.
+    pub fn set_color(&mut self, index: usize, color: Color) {
+        if let Some(palette_color) = self.colors.get_mut(index) {
+            palette_color.color = color;
+        }
+    }
+
+    /// Returns the index of the color, adding it to the end of the palette if it isn't in there yet.
+    ///
+    /// Returns `None` if the color is new and the palette is full.
+    pub fn index_of_or_add(&mut self, color: Color) -> Option<usize> {
+        self.index_of_color(color)
+            .or_else(|| self.add_color(color).then(|| self.count() - 1))
+    }
+
+    /// Returns the indices of all entries that match the color within the tolerance.
+    pub fn indices_matching(&self, color: Color, tolerance: f32) -> Vec<usize> {
+        self.colors
+            .iter()
+            .enumerate()
+            .filter(|(_, palette_color)| colors_match(palette_color.color, color, tolerance))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    pub fn palette_color(&self, index: usize) -> Option<&PaletteColor> {
+        self.colors.get(index)
+    }
+
+    pub fn set_material_property(&mut self, index: usize, property: MaterialProperty, value: f32) {
+        if let Some(palette_color) = self.colors.get_mut(index) {
+            palette_color.set_material_property(property, value);
+        }
+    }
+
+    pub fn palette_colors(&self) -> &[PaletteColor] {
+        &self.colors
     }
 
     pub fn all_colors(&self) -> Vec<Color> {
-        self.colors.clone()
+        self.colors.iter().map(|color| color.color).collect()
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected_index
     }
 
     pub fn count(&self) -> usize {
@@ -56,7 +218,9 @@ impl ColorLibrary {
     }
 
     pub fn selected_color(&self) -> Option<Color> {
-        self.colors.get(self.selected_index).cloned()
+        self.colors
+            .get(self.selected_index)
+            .map(|color| color.color)
     }
 
     pub fn select_index(&mut self, index: usize) {
@@ -67,8 +231,10 @@ impl ColorLibrary {
         self.selected_index = index;
     }
 
+    /// Selects the first entry with this color, entries are told apart by index so prefer `select_index`.
+    #[allow(unused)]
     pub fn select_color(&mut self, color: Color) -> bool {
-        let found_index = self.find_index_of_color(color);
+        let found_index = self.index_of_color(color);
 
         if let Some(index) = found_index {
             self.select_index(index)
@@ -77,23 +243,105 @@ impl ColorLibrary {
         found_index.is_some()
     }
 
+    /// Selects the given color, adding it to the library first if it isn't in there yet.
+    pub fn select_or_add_color(&mut self, color: Color) {
+        if let Some(index) = self.index_of_or_add(color) {
+            self.select_index(index);
+        }
+    }
+
     pub fn select_next(&mut self) {
-        self.select_index((self.selected_index + 1) % self.count());
+        if self.count() > 0 {
+            self.select_index((self.selected_index + 1) % self.count());
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if self.count() > 0 {
+            self.select_index((self.selected_index + self.count() - 1) % self.count());
+        }
+    }
+
+    pub fn index_of_color(&self, color: Color) -> Option<usize> {
+        self.colors.iter().position(|e| e.color == color)
     }
+}
+
+/// Returns where an entry at `index` ends up after the entry at `from` is moved to `to`.
+pub fn moved_index(index: usize, from: usize, to: usize) -> usize {
+    if index == from {
+        to
+    } else if from < index && index <= to {
+        index - 1
+    } else if to <= index && index < from {
+        index + 1
+    } else {
+        index
+    }
+}
+
+/// Returns where an entry at `index` ends up after the entry at `removed` is removed.
+pub fn index_after_removal(index: usize, removed: usize) -> usize {
+    if index > removed {
+        index - 1
+    } else {
+        index
+    }
+}
 
-    fn find_index_of_color(&self, color: Color) -> Option<usize> {
-        self.colors.iter().position(|e| *e == color)
+/// Moves blocks to the entry of the new palette with the nearest color, so swapping palettes doesn't recolor the scene.
+///
+/// Blocks with an index outside the old palette get the first entry.
+pub fn remap_to_palette(chunk: &mut Chunk, old: &ColorLibrary, new: &ColorLibrary) {
+    if new.count() == 0 {
+        return;
     }
+
+    let mapping = nearest_palette_indices(old, new);
+
+    chunk.remap_palette_indices(|index| mapping.get(index).copied().unwrap_or(0));
+}
+
+/// Returns for every entry of the old palette the index of the nearest color in the new palette, compared in OKLab.
+fn nearest_palette_indices(old: &ColorLibrary, new: &ColorLibrary) -> Vec<usize> {
+    let new_points: Vec<Vec3> = new.all_colors().into_iter().map(color_to_oklab).collect();
+
+    old.all_colors()
+        .into_iter()
+        .map(|color| {
+            let point = color_to_oklab(color);
+
+            new_points
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| {
+                    a.distance_squared(point)
+                        .total_cmp(&b.distance_squared(point))
+                })
+                .map_or(0, |(index, _)| index)
+        })
+        .collect()
+}
+
+/// Returns true if the colors differ by at most `tolerance` on every RGBA channel.
+pub fn colors_match(a: Color, b: Color, tolerance: f32) -> bool {
+    a.as_rgba_f32()
+        .iter()
+        .zip(b.as_rgba_f32().iter())
+        .all(|(a, b)| (a - b).abs() <= tolerance)
 }
 
+/// Sent when a color is clicked, in select mode this also recolors the selected blocks.
+///
+/// Names the palette entry by index, entries can share a color but differ in name or material.
 #[derive(Event)]
 pub struct OnColorClicked {
-    pub color: Color,
+    pub index: usize,
 }
 
 impl OnColorClicked {
-    pub fn new(color: Color) -> Self {
-        Self { color }
+    pub fn new(index: usize) -> Self {
+        Self { index }
     }
 }
 
@@ -102,12 +350,14 @@ fn switch_to_clicked_color(
     mut color_library: ResMut<ColorLibrary>,
 ) {
     for event in on_clicked.iter() {
-        color_library.select_color(event.color);
+        color_library.select_index(event.index);
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::{newtypes::coordinate::Coordinate, world::block::Block};
+
     use super::*;
 
     #[test]
@@ -206,6 +456,184 @@ mod tests {
         assert_eq!(looped_to_first, Some(Color::RED));
     }
 
+    #[test]
+    fn can_select_previous_color_and_loop_to_last() {
+        let mut library = create_rgb_library();
+        library.select_color(Color::GREEN);
+
+        library.select_previous();
+
+        assert_eq!(library.selected_color(), Some(Color::RED));
+
+        library.select_previous();
+
+        assert_eq!(library.selected_color(), Some(Color::BLUE));
+    }
+
+    #[test]
+    fn cycling_empty_library_does_nothing() {
+        let mut library = ColorLibrary::empty();
+
+        library.select_next();
+        library.select_previous();
+
+        assert_eq!(library.selected_color(), None);
+    }
+
+    #[test]
+    fn can_select_or_add_color() {
+        let mut library = create_rgb_library();
+
+        library.select_or_add_color(Color::GREEN);
+
+        assert_eq!(library.count(), 3);
+        assert_eq!(library.selected_color(), Some(Color::GREEN));
+
+        library.select_or_add_color(Color::PURPLE);
+
+        assert_eq!(library.count(), 4);
+        assert_eq!(library.selected_color(), Some(Color::PURPLE));
+    }
+
+    #[test]
+    fn colors_match_within_tolerance() {
+        let color = Color::rgb(0.5, 0.5, 0.5);
+        let similar_color = Color::rgb(0.52, 0.48, 0.5);
+
+        assert!(colors_match(color, color, 0.0));
+        assert!(!colors_match(color, similar_color, 0.0));
+        assert!(colors_match(color, similar_color, 0.05));
+    }
+
+    #[test]
+    fn editing_an_entry_keeps_its_index() {
+        let mut library = create_rgb_library();
+
+        library.set_color(1, Color::YELLOW);
+
+        assert_eq!(library.color_at(1), Some(Color::YELLOW));
+        assert_eq!(library.index_of_or_add(Color::YELLOW), Some(1));
+        assert_eq!(library.index_of_or_add(Color::PURPLE), Some(3));
+        assert_eq!(library.color_at(4), None);
+    }
+
+    #[test]
+    fn full_palette_doesnt_grow() {
+        let colors = vec![PaletteColor::new(Color::BLACK); ColorLibrary::MAX_COLORS];
+        let mut library = ColorLibrary::from_colors("full", colors);
+
+        assert!(!library.add_color(Color::RED));
+        assert_eq!(library.index_of_or_add(Color::RED), None);
+        assert_eq!(library.index_of_or_add(Color::BLACK), Some(0));
+        assert_eq!(library.count(), ColorLibrary::MAX_COLORS);
+    }
+
+    #[test]
+    fn finds_indices_matching_color() {
+        let mut library = create_rgb_library();
+        library.add_color(Color::rgb(0.95, 0.0, 0.0));
+
+        assert_eq!(library.indices_matching(Color::RED, 0.0), vec![0]);
+        assert_eq!(library.indices_matching(Color::RED, 0.1), vec![0, 3]);
+    }
+
+    #[test]
+    fn can_remove_color() {
+        let mut library = create_rgb_library();
+        library.select_color(Color::BLUE);
+
+        let removed = library.remove_color(0);
+
+        assert_eq!(removed.map(|removed| removed.color), Some(Color::RED));
+        assert_eq!(library.all_colors(), vec![Color::GREEN, Color::BLUE]);
+        assert_eq!(library.selected_color(), Some(Color::BLUE));
+    }
+
+    #[test]
+    fn removing_last_selected_color_selects_previous() {
+        let mut library = create_rgb_library();
+        library.select_color(Color::BLUE);
+
+        library.remove_color(2);
+
+        assert_eq!(library.selected_color(), Some(Color::GREEN));
+        assert_eq!(library.remove_color(5), None);
+    }
+
+    #[test]
+    fn selection_follows_moved_colors() {
+        let mut library = create_rgb_library();
+        library.select_color(Color::RED);
+
+        library.move_color(0, 2);
+
+        assert_eq!(
+            library.all_colors(),
+            vec![Color::GREEN, Color::BLUE, Color::RED]
+        );
+        assert_eq!(library.selected_color(), Some(Color::RED));
+
+        library.move_color(1, 0);
+
+        assert_eq!(library.selected_color(), Some(Color::RED));
+        assert_eq!(library.selected_index(), 2);
+    }
+
+    #[test]
+    fn indices_follow_moved_and_removed_entries() {
+        assert_eq!(moved_index(0, 0, 2), 2);
+        assert_eq!(moved_index(1, 0, 2), 0);
+        assert_eq!(moved_index(2, 0, 2), 1);
+        assert_eq!(moved_index(3, 0, 2), 3);
+        assert_eq!(moved_index(0, 2, 0), 1);
+        assert_eq!(moved_index(2, 2, 0), 0);
+
+        assert_eq!(index_after_removal(1, 1), 1);
+        assert_eq!(index_after_removal(3, 1), 2);
+        assert_eq!(index_after_removal(0, 1), 0);
+    }
+
+    #[test]
+    fn can_rename_palette_and_colors() {
+        let mut library = create_rgb_library();
+
+        library.set_name("primaries");
+        library.rename_color(1, "grass");
+
+        assert_eq!(library.name(), "primaries");
+        assert_eq!(library.color_name(1), Some("grass"));
+        assert_eq!(library.color_name(0), Some(""));
+        assert_eq!(library.color_name(3), None);
+    }
+
+    #[test]
+    fn swapped_palette_keeps_blocks_on_the_nearest_color() {
+        let old = create_rgb_library();
+        let mut new = ColorLibrary::empty();
+        new.add_color(Color::rgb(0.0, 0.0, 0.9));
+        new.add_color(Color::rgb(0.9, 0.0, 0.0));
+
+        let mut chunk = Chunk::empty(2);
+        chunk.set_block(Coordinate::new(0, 0, 0), Some(Block::new(0)));
+        chunk.set_block(Coordinate::new(1, 0, 0), Some(Block::new(2)));
+        chunk.set_block(Coordinate::new(0, 1, 0), Some(Block::new(7)));
+
+        remap_to_palette(&mut chunk, &old, &new);
+
+        assert_eq!(
+            chunk.get_block(Coordinate::new(0, 0, 0)),
+            Some(Block::new(1))
+        );
+        assert_eq!(
+            chunk.get_block(Coordinate::new(1, 0, 0)),
+            Some(Block::new(0))
+        );
+        assert_eq!(
+            chunk.get_block(Coordinate::new(0, 1, 0)),
+            Some(Block::new(0))
+        );
+    }
+
     fn create_rgb_library() -> ColorLibrary {
         let mut library = ColorLibrary::empty();
 
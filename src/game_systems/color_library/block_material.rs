@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::PaletteColor;
+
+/// How the blocks of a palette entry are shaded, transparency comes from the alpha of the entry's color.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockMaterial {
+    /// How strongly the block glows in its own color, 0 is no glow.
+    pub emissive: f32,
+    pub metallic: f32,
+    pub roughness: f32,
+}
+
+impl Default for BlockMaterial {
+    fn default() -> Self {
+        // Same values as the default `StandardMaterial`.
+        Self {
+            emissive: 0.0,
+            metallic: 0.0,
+            roughness: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaterialProperty {
+    Emissive,
+    Metallic,
+    Roughness,
+    Alpha,
+}
+
+impl MaterialProperty {
+    pub const ALL: [MaterialProperty; 4] = [
+        MaterialProperty::Emissive,
+        MaterialProperty::Metallic,
+        MaterialProperty::Roughness,
+        MaterialProperty::Alpha,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MaterialProperty::Emissive => "Emissive",
+            MaterialProperty::Metallic => "Metallic",
+            MaterialProperty::Roughness => "Roughness",
+            MaterialProperty::Alpha => "Alpha",
+        }
+    }
+
+    fn max_value(&self) -> f32 {
+        match self {
+            MaterialProperty::Emissive => 10.0,
+            _ => 1.0,
+        }
+    }
+}
+
+impl PaletteColor {
+    pub fn material_property(&self, property: MaterialProperty) -> f32 {
+        match property {
+            MaterialProperty::Emissive => self.material.emissive,
+            MaterialProperty::Metallic => self.material.metallic,
+            MaterialProperty::Roughness => self.material.roughness,
+            MaterialProperty::Alpha => self.color.a(),
+        }
+    }
+
+    pub fn set_material_property(&mut self, property: MaterialProperty, value: f32) {
+        let value = value.clamp(0.0, property.max_value());
+
+        match property {
+            MaterialProperty::Emissive => self.material.emissive = value,
+            MaterialProperty::Metallic => self.material.metallic = value,
+            MaterialProperty::Roughness => self.material.roughness = value,
+            MaterialProperty::Alpha => {
+                self.color.set_a(value);
+            }
+        }
+    }
+
+    pub fn to_standard_material(&self) -> StandardMaterial {
+        let alpha_mode = if self.color.a() < 1.0 {
+            AlphaMode::Blend
+        } else {
+            AlphaMode::Opaque
+        };
+
+        StandardMaterial {
+            base_color: self.color,
+            emissive: self.color.with_a(1.0) * self.material.emissive,
+            metallic: self.material.metallic,
+            perceptual_roughness: self.material.roughness,
+            alpha_mode,
+            ..default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_entry_renders_as_opaque_default_material() {
+        let material = PaletteColor::new(Color::RED).to_standard_material();
+        let default_material = StandardMaterial::default();
+
+        assert_eq!(material.base_color, Color::RED);
+        assert_eq!(material.alpha_mode, AlphaMode::Opaque);
+        assert_eq!(material.emissive, Color::rgb(0.0, 0.0, 0.0));
+        assert_eq!(
+            material.perceptual_roughness,
+            default_material.perceptual_roughness
+        );
+        assert_eq!(material.metallic, default_material.metallic);
+    }
+
+    #[test]
+    fn transparent_entry_is_blended() {
+        let mut glass = PaletteColor::new(Color::CYAN);
+
+        glass.set_material_property(MaterialProperty::Alpha, 0.3);
+
+        let material = glass.to_standard_material();
+
+        assert_eq!(material.alpha_mode, AlphaMode::Blend);
+        assert_eq!(material.base_color.a(), 0.3);
+    }
+
+    #[test]
+    fn emissive_entry_glows_in_its_own_color() {
+        let mut lamp = PaletteColor::new(Color::rgb(1.0, 0.5, 0.0));
+
+        lamp.set_material_property(MaterialProperty::Emissive, 2.0);
+
+        let emissive = lamp.to_standard_material().emissive;
+
+        assert_eq!(emissive.r(), 2.0);
+        assert_eq!(emissive.g(), 1.0);
+    }
+
+    #[test]
+    fn properties_are_clamped() {
+        let mut entry = PaletteColor::new(Color::WHITE);
+
+        entry.set_material_property(MaterialProperty::Roughness, 1.5);
+        entry.set_material_property(MaterialProperty::Metallic, -1.0);
+
+        assert_eq!(entry.material_property(MaterialProperty::Roughness), 1.0);
+        assert_eq!(entry.material_property(MaterialProperty::Metallic), 0.0);
+    }
+}
@@ -0,0 +1,164 @@
+use bevy::prelude::*;
+
+/// Ways to generate palette entries from a base color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Harmony {
+    Complementary,
+    Analogous,
+    Triadic,
+}
+
+impl Harmony {
+    /// Returns the colors that go with the base color, the base color itself is not included.
+    pub fn generate(&self, base: Color) -> Vec<Color> {
+        let hue_offsets: &[f32] = match self {
+            Harmony::Complementary => &[180.0],
+            Harmony::Analogous => &[-30.0, 30.0],
+            Harmony::Triadic => &[120.0, 240.0],
+        };
+
+        hue_offsets
+            .iter()
+            .map(|offset| rotate_hue(base, *offset))
+            .collect()
+    }
+}
+
+/// Returns `steps` colors going from `start` to `end`, both included, evenly spaced in OKLab.
+pub fn gradient(start: Color, end: Color, steps: usize) -> Vec<Color> {
+    let start_lab = color_to_oklab(start);
+    let end_lab = color_to_oklab(end);
+
+    match steps {
+        0 => Vec::new(),
+        1 => vec![start],
+        _ => (0..steps)
+            .map(|step| {
+                let amount = step as f32 / (steps - 1) as f32;
+                let alpha = start.a() + (end.a() - start.a()) * amount;
+
+                oklab_to_color(start_lab.lerp(end_lab, amount), alpha)
+            })
+            .collect(),
+    }
+}
+
+/// Rotates the hue in OKLCh, which keeps the perceived lightness the same.
+fn rotate_hue(color: Color, degrees: f32) -> Color {
+    let lab = color_to_oklab(color);
+    let chroma = Vec2::new(lab.y, lab.z);
+    let rotated = Vec2::from_angle(degrees.to_radians()).rotate(chroma);
+
+    oklab_to_color(Vec3::new(lab.x, rotated.x, rotated.y), color.a())
+}
+
+/// Converts a color to OKLab as (lightness, a, b).
+pub fn color_to_oklab(color: Color) -> Vec3 {
+    let [r, g, b, _] = color.as_linear_rgba_f32();
+
+    let l = 0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b;
+    let m = 0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b;
+    let s = 0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b;
+
+    let (l, m, s) = (l.cbrt(), m.cbrt(), s.cbrt());
+
+    Vec3::new(
+        0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    )
+}
+
+/// Converts OKLab back to a color, channels that fall outside of sRGB are clamped.
+pub fn oklab_to_color(lab: Vec3, alpha: f32) -> Color {
+    let l = lab.x + 0.396_337_78 * lab.y + 0.215_803_76 * lab.z;
+    let m = lab.x - 0.105_561_346 * lab.y - 0.063_854_17 * lab.z;
+    let s = lab.x - 0.089_484_18 * lab.y - 1.291_485_5 * lab.z;
+
+    let (l, m, s) = (l.powi(3), m.powi(3), s.powi(3));
+
+    let r = 4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s;
+    let g = -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s;
+    let b = -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s;
+
+    Color::rgba_linear(
+        r.clamp(0.0, 1.0),
+        g.clamp(0.0, 1.0),
+        b.clamp(0.0, 1.0),
+        alpha,
+    )
+    .as_rgba()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_systems::color_library::colors_match;
+
+    use super::*;
+
+    #[test]
+    fn oklab_round_trips() {
+        for color in [Color::RED, Color::CYAN, Color::rgb(0.2, 0.5, 0.7)] {
+            let converted = oklab_to_color(color_to_oklab(color), 1.0);
+
+            assert!(colors_match(color, converted, 0.001));
+        }
+    }
+
+    #[test]
+    fn white_has_full_lightness_and_no_chroma() {
+        let lab = color_to_oklab(Color::WHITE);
+
+        assert!((lab.x - 1.0).abs() < 0.001);
+        assert!(lab.y.abs() < 0.001 && lab.z.abs() < 0.001);
+    }
+
+    #[test]
+    fn complementary_of_complementary_is_base() {
+        let base = Color::rgb(0.3, 0.5, 0.4);
+
+        let complementary = Harmony::Complementary.generate(base)[0];
+        let back = Harmony::Complementary.generate(complementary)[0];
+
+        assert!(!colors_match(base, complementary, 0.05));
+        assert!(colors_match(base, back, 0.01));
+    }
+
+    #[test]
+    fn harmonies_keep_lightness() {
+        let base = Color::rgb(0.4, 0.45, 0.5);
+        let lightness = color_to_oklab(base).x;
+
+        for harmony in [Harmony::Analogous, Harmony::Triadic] {
+            let colors = harmony.generate(base);
+
+            assert_eq!(colors.len(), 2);
+            assert!(colors
+                .iter()
+                .all(|color| (color_to_oklab(*color).x - lightness).abs() < 0.01));
+        }
+    }
+
+    #[test]
+    fn gradient_includes_both_ends() {
+        let colors = gradient(Color::BLACK, Color::WHITE, 5);
+
+        assert_eq!(colors.len(), 5);
+        assert!(colors_match(colors[0], Color::BLACK, 0.001));
+        assert!(colors_match(colors[4], Color::WHITE, 0.001));
+
+        let lightness: Vec<f32> = colors
+            .iter()
+            .map(|color| color_to_oklab(*color).x)
+            .collect();
+
+        assert!((lightness[2] - 0.5).abs() < 0.01);
+        assert!(lightness.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn gradient_handles_small_step_counts() {
+        assert!(gradient(Color::RED, Color::BLUE, 0).is_empty());
+        assert_eq!(gradient(Color::RED, Color::BLUE, 1), vec![Color::RED]);
+    }
+}
@@ -0,0 +1,155 @@
+use bevy::{input::mouse::MouseWheel, prelude::*};
+
+use crate::player::editor_actions::{ActionState, EditorAction, OnEditorAction};
+
+use super::ColorLibrary;
+
+/*
+    These select palette entries directly instead of sending `OnColorClicked`,
+    color clicks also recolor the selection in select mode and are only sent by clicking a color.
+*/
+
+pub struct ColorHotbarPlugin;
+
+impl Plugin for ColorHotbarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                select_color_with_hotbar_actions,
+                cycle_colors_with_scroll,
+                cycle_colors_with_actions,
+            ),
+        );
+    }
+}
+
+/// How many colors have a hotbar slot, the default bindings use keys 1 - 9.
+const HOTBAR_SIZE: usize = 9;
+
+fn select_color_with_hotbar_actions(
+    mut on_action: EventReader<OnEditorAction>,
+    mut color_library: ResMut<ColorLibrary>,
+) {
+    for event in on_action.iter() {
+        if let EditorAction::SelectHotbarColor(index) = event.action {
+            color_library.select_index(index);
+        }
+    }
+}
+
+/// Scrolling only cycles colors while `CycleColors` is held, so it doesn't fight the camera.
+fn cycle_colors_with_scroll(
+    action_state: Res<ActionState>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut color_library: ResMut<ColorLibrary>,
+) {
+    if !action_state.is_held(EditorAction::CycleColors) {
+        mouse_wheel.clear();
+        return;
+    }
+
+    let scroll: f32 = mouse_wheel.iter().map(|event| event.y).sum();
+
+    if scroll == 0.0 {
+        return;
+    }
+
+    if scroll > 0.0 {
+        color_library.select_previous();
+    } else {
+        color_library.select_next();
+    }
+}
+
+/// Steps through the colors with actions, the default bindings put these on the gamepad bumpers.
+fn cycle_colors_with_actions(
+    mut on_action: EventReader<OnEditorAction>,
+    mut color_library: ResMut<ColorLibrary>,
+) {
+    for event in on_action.iter() {
+        match event.action {
+            EditorAction::NextColor => color_library.select_next(),
+            EditorAction::PreviousColor => color_library.select_previous(),
+            _ => {}
+        }
+    }
+}
+
+/// The number shown on a selector button, only the first nine colors have a slot.
+pub fn hotbar_label(index: usize) -> Option<String> {
+    (index < HOTBAR_SIZE).then(|| (index + 1).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_systems::color_library::{
+        switch_to_clicked_color, OnColorClicked, PaletteColor,
+    };
+
+    use super::*;
+
+    /// Two entries share a color, they differ in name.
+    fn repeating_palette() -> ColorLibrary {
+        ColorLibrary::from_colors(
+            "repeating",
+            vec![
+                PaletteColor::with_name(Color::RED, "brick"),
+                PaletteColor::new(Color::GREEN),
+                PaletteColor::with_name(Color::RED, "roof"),
+            ],
+        )
+    }
+
+    fn hotbar_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<OnEditorAction>()
+            .add_event::<OnColorClicked>()
+            .insert_resource(repeating_palette())
+            .add_systems(
+                Update,
+                (
+                    select_color_with_hotbar_actions,
+                    cycle_colors_with_actions,
+                    switch_to_clicked_color,
+                ),
+            );
+        app
+    }
+
+    fn send_action(app: &mut App, action: EditorAction) {
+        app.world.send_event(OnEditorAction::new(action));
+        app.update();
+    }
+
+    #[test]
+    fn cycling_reaches_entries_that_repeat_a_color() {
+        let mut app = hotbar_app();
+
+        send_action(&mut app, EditorAction::NextColor);
+        send_action(&mut app, EditorAction::NextColor);
+
+        assert_eq!(app.world.resource::<ColorLibrary>().selected_index(), 2);
+
+        send_action(&mut app, EditorAction::PreviousColor);
+
+        assert_eq!(app.world.resource::<ColorLibrary>().selected_index(), 1);
+    }
+
+    #[test]
+    fn hotbar_selects_entries_that_repeat_a_color() {
+        let mut app = hotbar_app();
+
+        send_action(&mut app, EditorAction::SelectHotbarColor(2));
+
+        assert_eq!(app.world.resource::<ColorLibrary>().selected_index(), 2);
+    }
+
+    #[test]
+    fn only_first_nine_colors_get_a_label() {
+        assert_eq!(hotbar_label(0), Some("1".to_string()));
+        assert_eq!(hotbar_label(8), Some("9".to_string()));
+        assert_eq!(hotbar_label(9), None);
+    }
+}
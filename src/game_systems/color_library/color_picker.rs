@@ -0,0 +1,498 @@
+mod interaction;
+
+use bevy::{prelude::*, ui::RelativeCursorPosition};
+
+use self::interaction::PickerInteractionPlugin;
+use super::{color_harmony::Harmony, ColorLibrary};
+use crate::{
+    game_systems::text_field::{spawn_text_field, TextField},
+    player::editor_actions::{action_triggered, EditorAction, OnEditorAction},
+};
+
+pub struct ColorPickerPlugin;
+
+impl Plugin for ColorPickerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(PickerInteractionPlugin)
+            .init_resource::<ColorPicker>()
+            .add_systems(Startup, spawn_color_picker)
+            .add_systems(
+                Update,
+                (
+                    toggle_color_picker,
+                    update_picker_visibility,
+                    update_picker_ui.run_if(resource_changed::<ColorPicker>()),
+                ),
+            );
+    }
+}
+
+const SLIDER_WIDTH: f32 = 200.0;
+const GRADIENT_STEPS: usize = 5;
+const TEXT_COLOR: Color = Color::WHITE;
+const PANEL_COLOR: Color = Color::rgba(0.1, 0.1, 0.1, 0.9);
+const TRACK_COLOR: Color = Color::DARK_GRAY;
+
+/// The color that is being mixed in the picker panel, stored as hue (0 - 360), saturation and value (0 - 1).
+#[derive(Resource, Debug, Clone, PartialEq, Default)]
+pub struct ColorPicker {
+    hue: f32,
+    saturation: f32,
+    value: f32,
+    is_open: bool,
+}
+
+impl ColorPicker {
+    pub fn color(&self) -> Color {
+        hsv_to_color(self.hue, self.saturation, self.value)
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        let (hue, saturation, value) = color_to_hsv(color);
+
+        // Grays have no hue, keeping the previous one makes the hue slider not jump around.
+        if saturation > 0.0 && value > 0.0 {
+            self.hue = hue;
+        }
+
+        self.saturation = saturation;
+        self.value = value;
+    }
+
+    /// Returns the value of a channel, normalized to 0 - 1.
+    pub fn channel(&self, channel: PickerChannel) -> f32 {
+        let color = self.color();
+
+        match channel {
+            PickerChannel::Hue => self.hue / 360.0,
+            PickerChannel::Saturation => self.saturation,
+            PickerChannel::Value => self.value,
+            PickerChannel::Red => color.r(),
+            PickerChannel::Green => color.g(),
+            PickerChannel::Blue => color.b(),
+        }
+    }
+
+    /// Sets a channel from a value normalized to 0 - 1.
+    pub fn set_channel(&mut self, channel: PickerChannel, value: f32) {
+        let value = value.clamp(0.0, 1.0);
+        let color = self.color();
+
+        match channel {
+            PickerChannel::Hue => self.hue = value * 360.0,
+            PickerChannel::Saturation => self.saturation = value,
+            PickerChannel::Value => self.value = value,
+            PickerChannel::Red => self.set_color(Color::rgb(value, color.g(), color.b())),
+            PickerChannel::Green => self.set_color(Color::rgb(color.r(), value, color.b())),
+            PickerChannel::Blue => self.set_color(Color::rgb(color.r(), color.g(), value)),
+        }
+    }
+
+    /// Mixes the picked color halfway towards the other color.
+    pub fn mix_with(&mut self, other: Color) {
+        let color = self.color();
+
+        self.set_color(Color::rgb(
+            (color.r() + other.r()) / 2.0,
+            (color.g() + other.g()) / 2.0,
+            (color.b() + other.b()) / 2.0,
+        ));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickerChannel {
+    Hue,
+    Saturation,
+    Value,
+    Red,
+    Green,
+    Blue,
+}
+
+impl PickerChannel {
+    const ALL: [PickerChannel; 6] = [
+        PickerChannel::Hue,
+        PickerChannel::Saturation,
+        PickerChannel::Value,
+        PickerChannel::Red,
+        PickerChannel::Green,
+        PickerChannel::Blue,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            PickerChannel::Hue => "H",
+            PickerChannel::Saturation => "S",
+            PickerChannel::Value => "V",
+            PickerChannel::Red => "R",
+            PickerChannel::Green => "G",
+            PickerChannel::Blue => "B",
+        }
+    }
+}
+
+#[derive(Component)]
+struct ColorPickerPanel;
+
+#[derive(Component, Clone, Copy)]
+struct PickerSlider {
+    channel: PickerChannel,
+}
+
+#[derive(Component, Clone, Copy)]
+struct SliderHandle {
+    channel: PickerChannel,
+}
+
+#[derive(Component)]
+struct PickerPreview;
+
+#[derive(Component)]
+struct HexField;
+
+#[derive(Component, Clone, Copy)]
+enum PickerButton {
+    AddColor,
+    MixWithSelected,
+    SetSelected,
+    AddHarmony(Harmony),
+    AddGradientToSelected,
+}
+
+impl PickerButton {
+    fn label(&self) -> &'static str {
+        match self {
+            PickerButton::AddColor => "Add color",
+            PickerButton::MixWithSelected => "Mix with selected",
+            PickerButton::SetSelected => "Set selected color",
+            PickerButton::AddHarmony(Harmony::Complementary) => "+ Complementary",
+            PickerButton::AddHarmony(Harmony::Analogous) => "+ Analogous",
+            PickerButton::AddHarmony(Harmony::Triadic) => "+ Triadic",
+            PickerButton::AddGradientToSelected => "+ Gradient to selected",
+        }
+    }
+}
+
+fn toggle_color_picker(
+    mut on_action: EventReader<OnEditorAction>,
+    mut color_picker: ResMut<ColorPicker>,
+    color_library: Res<ColorLibrary>,
+) {
+    if action_triggered(&mut on_action, EditorAction::TogglePicker) {
+        color_picker.is_open = !color_picker.is_open;
+
+        if color_picker.is_open {
+            if let Some(color) = color_library.selected_color() {
+                color_picker.set_color(color);
+            }
+        }
+    }
+}
+
+fn update_picker_visibility(
+    color_picker: Res<ColorPicker>,
+    mut panels: Query<&mut Style, With<ColorPickerPanel>>,
+) {
+    let display = if color_picker.is_open {
+        Display::Flex
+    } else {
+        Display::None
+    };
+
+    for mut style in panels.iter_mut() {
+        if style.display != display {
+            style.display = display;
+        }
+    }
+}
+
+fn update_picker_ui(
+    color_picker: Res<ColorPicker>,
+    mut handles: Query<(&SliderHandle, &mut Style)>,
+    mut previews: Query<&mut BackgroundColor, With<PickerPreview>>,
+    mut hex_fields: Query<&mut TextField, With<HexField>>,
+) {
+    for (handle, mut style) in handles.iter_mut() {
+        style.left = Val::Percent(color_picker.channel(handle.channel) * 100.0);
+    }
+
+    for mut background in previews.iter_mut() {
+        background.0 = color_picker.color();
+    }
+
+    for mut hex_field in hex_fields
+        .iter_mut()
+        .filter(|hex_field| !hex_field.is_focused())
+    {
+        hex_field.text = color_to_hex(color_picker.color());
+    }
+}
+
+// UI
+
+fn spawn_color_picker(mut commands: Commands, color_picker: Res<ColorPicker>) {
+    commands
+        .spawn((
+            Name::new("Color picker panel"),
+            ColorPickerPanel,
+            Interaction::default(),
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(10.0)),
+                    row_gap: Val::Px(6.0),
+                    ..default()
+                },
+                background_color: PANEL_COLOR.into(),
+                ..default()
+            },
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Name::new("Picker preview"),
+                PickerPreview,
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Px(30.0),
+                        ..default()
+                    },
+                    background_color: color_picker.color().into(),
+                    ..default()
+                },
+            ));
+
+            for channel in PickerChannel::ALL {
+                spawn_slider(panel, channel, color_picker.channel(channel));
+            }
+
+            spawn_hex_field(panel, &color_picker);
+
+            for button in [
+                PickerButton::MixWithSelected,
+                PickerButton::SetSelected,
+                PickerButton::AddColor,
+                PickerButton::AddHarmony(Harmony::Complementary),
+                PickerButton::AddHarmony(Harmony::Analogous),
+                PickerButton::AddHarmony(Harmony::Triadic),
+                PickerButton::AddGradientToSelected,
+            ] {
+                spawn_picker_button(panel, button);
+            }
+        });
+}
+
+fn spawn_slider(panel: &mut ChildBuilder, channel: PickerChannel, value: f32) {
+    panel
+        .spawn((
+            Name::new("Picker slider row"),
+            NodeBundle {
+                style: Style {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(8.0),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|row| {
+            row.spawn(TextBundle::from_section(channel.label(), text_style()));
+
+            row.spawn((
+                Name::new("Picker slider"),
+                PickerSlider { channel },
+                RelativeCursorPosition::default(),
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(SLIDER_WIDTH),
+                        height: Val::Px(16.0),
+                        ..default()
+                    },
+                    background_color: TRACK_COLOR.into(),
+                    ..default()
+                },
+            ))
+            .with_children(|slider| {
+                slider.spawn((
+                    SliderHandle { channel },
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            left: Val::Percent(value * 100.0),
+                            width: Val::Px(4.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        background_color: Color::WHITE.into(),
+                        ..default()
+                    },
+                ));
+            });
+        });
+}
+
+fn spawn_hex_field(panel: &mut ChildBuilder, color_picker: &ColorPicker) {
+    let text_field = TextField::new(color_to_hex(color_picker.color()), 7, |character| {
+        character.is_ascii_hexdigit() || character == '#'
+    });
+
+    spawn_text_field(panel, text_field, Val::Px(100.0), HexField);
+}
+
+fn spawn_picker_button(panel: &mut ChildBuilder, button: PickerButton) {
+    panel
+        .spawn((
+            Name::new("Picker button"),
+            button,
+            ButtonBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(4.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: TRACK_COLOR.into(),
+                ..default()
+            },
+        ))
+        .with_children(|node| {
+            node.spawn(TextBundle::from_section(button.label(), text_style()));
+        });
+}
+
+fn text_style() -> TextStyle {
+    TextStyle {
+        font_size: 16.0,
+        color: TEXT_COLOR,
+        ..default()
+    }
+}
+
+// Conversions
+
+/// Converts hue (0 - 360), saturation and value (0 - 1) to a color.
+pub fn hsv_to_color(hue: f32, saturation: f32, value: f32) -> Color {
+    let chroma = value * saturation;
+    let sector = (hue.rem_euclid(360.0)) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    let offset = value - chroma;
+
+    Color::rgb(r + offset, g + offset, b + offset)
+}
+
+/// Converts a color to hue (0 - 360), saturation and value (0 - 1).
+pub fn color_to_hsv(color: Color) -> (f32, f32, f32) {
+    let (r, g, b) = (color.r(), color.g(), color.b());
+
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+    (hue, saturation, max)
+}
+
+pub fn color_to_hex(color: Color) -> String {
+    let [r, g, b, _] = color.as_rgba_u8();
+
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+pub fn color_from_hex(hex: &str) -> Option<Color> {
+    Color::hex(hex.trim()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_convert_hsv_to_color() {
+        assert_eq!(hsv_to_color(0.0, 1.0, 1.0), Color::rgb(1.0, 0.0, 0.0));
+        assert_eq!(hsv_to_color(120.0, 1.0, 1.0), Color::rgb(0.0, 1.0, 0.0));
+        assert_eq!(hsv_to_color(240.0, 1.0, 0.5), Color::rgb(0.0, 0.0, 0.5));
+        assert_eq!(hsv_to_color(60.0, 0.0, 1.0), Color::rgb(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn can_convert_color_to_hsv() {
+        assert_eq!(color_to_hsv(Color::rgb(1.0, 0.0, 0.0)), (0.0, 1.0, 1.0));
+        assert_eq!(color_to_hsv(Color::rgb(0.0, 0.5, 0.5)), (180.0, 1.0, 0.5));
+        assert_eq!(color_to_hsv(Color::rgb(0.0, 0.0, 0.0)), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn hsv_conversion_round_trips() {
+        let color = Color::rgb(0.2, 0.6, 0.4);
+
+        let (hue, saturation, value) = color_to_hsv(color);
+        let result = hsv_to_color(hue, saturation, value);
+
+        assert!((result.r() - color.r()).abs() < 0.0001);
+        assert!((result.g() - color.g()).abs() < 0.0001);
+        assert!((result.b() - color.b()).abs() < 0.0001);
+    }
+
+    #[test]
+    fn can_convert_hex() {
+        assert_eq!(color_to_hex(Color::rgb_u8(68, 170, 34)), "#44AA22");
+        assert_eq!(color_from_hex("#44aa22"), Some(Color::rgb_u8(68, 170, 34)));
+        assert_eq!(color_from_hex("fff"), Some(Color::rgb_u8(255, 255, 255)));
+        assert_eq!(color_from_hex("#44aa2"), None);
+    }
+
+    #[test]
+    fn picker_keeps_hue_for_grays() {
+        let mut picker = ColorPicker::default();
+        picker.set_channel(PickerChannel::Hue, 0.5);
+
+        picker.set_color(Color::GRAY);
+
+        assert_eq!(picker.channel(PickerChannel::Hue), 0.5);
+    }
+
+    #[test]
+    fn can_set_rgb_channels() {
+        let mut picker = ColorPicker::default();
+
+        picker.set_channel(PickerChannel::Red, 1.0);
+        picker.set_channel(PickerChannel::Blue, 1.0);
+
+        assert_eq!(picker.color(), Color::rgb(1.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn can_mix_colors() {
+        let mut picker = ColorPicker::default();
+        picker.set_color(Color::rgb(1.0, 0.0, 0.0));
+
+        picker.mix_with(Color::rgb(0.0, 0.0, 1.0));
+
+        assert_eq!(picker.color(), Color::rgb(0.5, 0.0, 0.5));
+    }
+}
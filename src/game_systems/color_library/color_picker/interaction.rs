@@ -0,0 +1,98 @@
+use bevy::{prelude::*, ui::RelativeCursorPosition};
+
+use crate::game_systems::{
+    color_library::{color_harmony::gradient, ColorLibrary, OnColorClicked},
+    text_field::OnTextFieldSubmitted,
+};
+
+use super::{color_from_hex, ColorPicker, HexField, PickerButton, PickerSlider, GRADIENT_STEPS};
+
+pub struct PickerInteractionPlugin;
+
+impl Plugin for PickerInteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (drag_sliders, button_interaction, apply_submitted_hex),
+        );
+    }
+}
+
+fn drag_sliders(
+    sliders: Query<(&PickerSlider, &Interaction, &RelativeCursorPosition)>,
+    mut color_picker: ResMut<ColorPicker>,
+) {
+    for (slider, interaction, cursor_position) in sliders.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        if let Some(position) = cursor_position.normalized {
+            if color_picker.channel(slider.channel) != position.x.clamp(0.0, 1.0) {
+                color_picker.set_channel(slider.channel, position.x);
+            }
+        }
+    }
+}
+
+fn button_interaction(
+    buttons: Query<(&PickerButton, &Interaction), Changed<Interaction>>,
+    mut color_picker: ResMut<ColorPicker>,
+    mut color_library: ResMut<ColorLibrary>,
+    mut on_clicked: EventWriter<OnColorClicked>,
+) {
+    for (button, interaction) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            PickerButton::AddColor => {
+                let color = color_picker.color();
+
+                color_library.select_or_add_color(color);
+                on_clicked.send(OnColorClicked::new(color_library.selected_index()));
+            }
+            PickerButton::SetSelected => {
+                let selected_index = color_library.selected_index();
+                color_library.set_color(selected_index, color_picker.color());
+            }
+            PickerButton::AddHarmony(harmony) => {
+                let base = color_picker.color();
+
+                for color in std::iter::once(base).chain(harmony.generate(base)) {
+                    color_library.index_of_or_add(color);
+                }
+            }
+            PickerButton::AddGradientToSelected => {
+                if let Some(selected_color) = color_library.selected_color() {
+                    for color in gradient(color_picker.color(), selected_color, GRADIENT_STEPS) {
+                        color_library.index_of_or_add(color);
+                    }
+                }
+            }
+            PickerButton::MixWithSelected => {
+                if let Some(selected_color) = color_library.selected_color() {
+                    color_picker.mix_with(selected_color);
+                }
+            }
+        }
+    }
+}
+
+fn apply_submitted_hex(
+    mut on_submitted: EventReader<OnTextFieldSubmitted>,
+    hex_fields: Query<(), With<HexField>>,
+    mut color_picker: ResMut<ColorPicker>,
+) {
+    for submitted in on_submitted
+        .iter()
+        .filter(|submitted| hex_fields.contains(submitted.field))
+    {
+        match color_from_hex(&submitted.text) {
+            Some(color) => color_picker.set_color(color),
+            // Resets the field to the current color.
+            None => color_picker.set_changed(),
+        }
+    }
+}
@@ -2,10 +2,8 @@ mod interaction;
 
 use bevy::prelude::*;
 
-use crate::player::editor_modes::EditorMode;
-
 use self::interaction::SelectorInteractionPlugin;
-use super::{ColorLibrary, OnColorClicked};
+use super::{color_hotbar::hotbar_label, ColorLibrary, OnColorClicked};
 
 pub struct ColorSelectorPlugin;
 
@@ -13,19 +11,29 @@ impl Plugin for ColorSelectorPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(SelectorInteractionPlugin)
             .add_systems(Startup, spawn_selector_buttons)
-            .add_systems(Update, update_highlighted_ui.run_if(in_state(EditorMode::Build)));
+            .add_systems(Update, update_highlighted_ui)
+            .add_systems(
+                Update,
+                rebuild_selector_buttons.run_if(resource_changed::<ColorLibrary>()),
+            );
     }
 }
 
+#[derive(Component)]
+struct SelectorButtonsContainer;
+
+/// Points at a palette entry by index, so entries with the same color are still separate buttons.
 #[derive(Component, Clone, Copy)]
 struct SelectorButton {
+    index: usize,
     color: Color,
     pub is_highlighted: bool,
 }
 
 impl SelectorButton {
-    fn new(color: Color) -> Self {
+    fn new(index: usize, color: Color) -> Self {
         Self {
+            index,
             color,
             is_highlighted: false,
         }
@@ -61,6 +69,7 @@ fn spawn_selector_buttons(mut commands: Commands, color_library: Res<ColorLibrar
     commands
         .spawn((
             Name::new("Selector buttons container"),
+            SelectorButtonsContainer,
             NodeBundle {
                 style: Style {
                     position_type: PositionType::Absolute,
@@ -73,12 +82,47 @@ fn spawn_selector_buttons(mut commands: Commands, color_library: Res<ColorLibrar
                 ..default()
             },
         ))
-        .with_children(|list| {
-            let buttons = build_buttons_from_colors(color_library.all_colors());
-            for node in build_buttons_ui(buttons, color_library.selected_color()) {
-                list.spawn((Name::new("Selector button"), node));
-            }
-        });
+        .with_children(|list| spawn_buttons(list, &color_library));
+}
+
+/// Replaces all buttons so they match the colors, order and selection in the library.
+fn rebuild_selector_buttons(
+    mut commands: Commands,
+    color_library: Res<ColorLibrary>,
+    containers: Query<Entity, With<SelectorButtonsContainer>>,
+) {
+    for container in containers.iter() {
+        commands
+            .entity(container)
+            .despawn_descendants()
+            .with_children(|list| spawn_buttons(list, &color_library));
+    }
+}
+
+fn spawn_buttons(list: &mut ChildBuilder, color_library: &ColorLibrary) {
+    let buttons = build_buttons_from_colors(color_library.all_colors());
+    let selected_index = color_library
+        .selected_color()
+        .map(|_| color_library.selected_index());
+
+    for (index, node) in build_buttons_ui(buttons, selected_index)
+        .into_iter()
+        .enumerate()
+    {
+        list.spawn((Name::new("Selector button"), node))
+            .with_children(|button| {
+                if let Some(label) = hotbar_label(index) {
+                    button.spawn(TextBundle::from_section(
+                        label,
+                        TextStyle {
+                            font_size: 14.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ));
+                }
+            });
+    }
 }
 
 fn update_highlighted_ui(
@@ -87,7 +131,7 @@ fn update_highlighted_ui(
 ) {
     for event in on_clicked.iter() {
         for (mut button, mut border) in buttons.iter_mut() {
-            update_button_highlight(&mut button, &mut border, event.color);
+            update_button_highlight(&mut button, &mut border, event.index);
         }
     }
 }
@@ -95,36 +139,37 @@ fn update_highlighted_ui(
 fn build_buttons_from_colors(colors: Vec<Color>) -> Vec<SelectorButton> {
     colors
         .into_iter()
-        .map(|color| SelectorButton::new(color))
+        .enumerate()
+        .map(|(index, color)| SelectorButton::new(index, color))
         .collect()
 }
 
 fn build_buttons_ui(
     buttons: Vec<SelectorButton>,
-    selected_color: Option<Color>,
+    selected_index: Option<usize>,
 ) -> Vec<(ButtonBundle, SelectorButton)> {
     buttons
         .into_iter()
         .map(|mut button| {
-            button.is_highlighted = check_if_selected(&button, selected_color);
+            button.is_highlighted = check_if_selected(&button, selected_index);
             button.to_ui()
         })
         .collect()
 }
 
-fn check_if_selected(button: &SelectorButton, selected_color: Option<Color>) -> bool {
-    Some(button.color) == selected_color
+fn check_if_selected(button: &SelectorButton, selected_index: Option<usize>) -> bool {
+    Some(button.index) == selected_index
 }
 
 fn update_button_highlight(
     button: &mut SelectorButton,
     border: &mut BorderColor,
-    selected_color: Color,
+    selected_index: usize,
 ) {
-    let has_selected_color = button.color == selected_color;
+    let is_selected = button.index == selected_index;
 
-    if button.is_highlighted != has_selected_color {
-        button.is_highlighted = has_selected_color;
+    if button.is_highlighted != is_selected {
+        button.is_highlighted = is_selected;
         border.0 = button.border_color();
     }
 }
@@ -135,14 +180,14 @@ mod tests {
 
     #[test]
     fn can_create_selector_button() {
-        let selector = SelectorButton::new(Color::GREEN);
+        let selector = SelectorButton::new(0, Color::GREEN);
 
         assert_eq!(selector.color, Color::GREEN);
     }
 
     #[test]
     fn can_build_ui_node_from_selector_button() {
-        let selector = SelectorButton::new(Color::GREEN);
+        let selector = SelectorButton::new(0, Color::GREEN);
 
         let node: (ButtonBundle, SelectorButton) = selector.to_ui();
 
@@ -151,7 +196,7 @@ mod tests {
 
     #[test]
     fn button_highlighted_changes_border_color() {
-        let mut selector = SelectorButton::new(Color::GREEN);
+        let mut selector = SelectorButton::new(0, Color::GREEN);
         selector.is_highlighted = true;
 
         let node: (ButtonBundle, SelectorButton) = selector.to_ui();
@@ -162,8 +207,7 @@ mod tests {
     #[test]
     fn selected_color_gets_highlighted() {
         let buttons = build_buttons_from_colors(vec![Color::RED, Color::GREEN]);
-        let nodes: Vec<(ButtonBundle, SelectorButton)> =
-            build_buttons_ui(buttons, Some(Color::GREEN));
+        let nodes: Vec<(ButtonBundle, SelectorButton)> = build_buttons_ui(buttons, Some(1));
 
         let selected_button = &nodes[1].0;
 
@@ -171,6 +215,20 @@ mod tests {
         assert_eq!(selected_button.border_color.0, Color::WHITE);
     }
 
+    #[test]
+    fn only_the_selected_entry_is_highlighted_when_colors_repeat() {
+        let buttons = build_buttons_from_colors(vec![Color::RED, Color::GREEN, Color::RED]);
+        let nodes: Vec<(ButtonBundle, SelectorButton)> = build_buttons_ui(buttons, Some(2));
+
+        let highlighted: Vec<usize> = nodes
+            .iter()
+            .filter(|(_, button)| button.is_highlighted)
+            .map(|(_, button)| button.index)
+            .collect();
+
+        assert_eq!(highlighted, vec![2]);
+    }
+
     #[test]
     fn correctly_updates_button_highlights_when_color_changed() {
         let mut buttons = build_buttons_from_colors(vec![Color::RED, Color::GREEN, Color::BLUE]);
@@ -180,7 +238,7 @@ mod tests {
         assert_eq!(count_highlighted_buttons(&buttons), 1);
 
         for button in buttons.iter_mut() {
-            update_button_highlight(button, &mut BorderColor(Color::WHITE), Color::GREEN);
+            update_button_highlight(button, &mut BorderColor(Color::WHITE), 1);
         }
 
         assert_eq!(find_selected_color(&buttons), Some(Color::GREEN));
@@ -18,7 +18,7 @@ fn button_interaction(
 ) {
     for (button, interaction) in buttons.iter() {
         if *interaction == Interaction::Pressed {
-            on_clicked.send(OnColorClicked::new(button.color));
+            on_clicked.send(OnColorClicked::new(button.index));
         }
     }
 }
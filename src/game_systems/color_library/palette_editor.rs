@@ -0,0 +1,512 @@
+use bevy::prelude::*;
+
+use crate::{
+    game_systems::text_field::{
+        accepts_any, spawn_text_field, text_field_style, OnTextFieldSubmitted, TextField,
+    },
+    player::editor_actions::{action_triggered, EditorAction, OnEditorAction},
+    world::chunk::Chunk,
+};
+
+use super::{
+    block_material::MaterialProperty,
+    index_after_removal, moved_index,
+    palette_formats::{OnExportPaletteRequest, OnImportPaletteRequest},
+    palette_storage::{
+        next_palette_name, saved_palette_names, OnLoadPaletteRequest, OnSavePaletteRequest,
+    },
+    quantize::OnQuantizePaletteRequest,
+    recent_colors::RecentColors,
+    ColorLibrary, PaletteColor,
+};
+
+pub struct PaletteEditorPlugin;
+
+impl Plugin for PaletteEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PaletteEditor>()
+            .add_systems(Startup, spawn_palette_editor)
+            .add_systems(
+                Update,
+                (
+                    toggle_palette_editor,
+                    update_editor_visibility,
+                    button_interaction,
+                    apply_submitted_names,
+                    (update_name_fields, update_material_texts)
+                        .run_if(resource_changed::<ColorLibrary>()),
+                ),
+            );
+    }
+}
+
+const MAX_NAME_LENGTH: usize = 24;
+const MAX_PATH_LENGTH: usize = 64;
+const DEFAULT_MAX_COLORS: &str = "16";
+const MATERIAL_STEP: f32 = 0.1;
+const PANEL_COLOR: Color = Color::rgba(0.1, 0.1, 0.1, 0.9);
+const BUTTON_COLOR: Color = Color::DARK_GRAY;
+
+#[derive(Resource, Default)]
+struct PaletteEditor {
+    is_open: bool,
+}
+
+#[derive(Component)]
+struct PaletteEditorPanel;
+
+#[derive(Component)]
+struct PaletteNameField;
+
+#[derive(Component)]
+struct ColorNameField;
+
+/// The file that palettes are imported from and exported to, like `palettes/forest.gpl`.
+#[derive(Component)]
+struct PaletteFileField;
+
+/// How many colors the scene is reduced to.
+#[derive(Component)]
+struct MaxColorsField;
+
+/// Shows the value of a material property of the selected color.
+#[derive(Component)]
+struct MaterialValueText(MaterialProperty);
+
+#[derive(Component, Clone, Copy)]
+enum PaletteButton {
+    MoveLeft,
+    MoveRight,
+    Remove,
+    Save,
+    NextPalette,
+    Import,
+    Export,
+    ReduceColors,
+    DecreaseMaterial(MaterialProperty),
+    IncreaseMaterial(MaterialProperty),
+}
+
+impl PaletteButton {
+    const EDIT_BUTTONS: [PaletteButton; 5] = [
+        PaletteButton::MoveLeft,
+        PaletteButton::MoveRight,
+        PaletteButton::Remove,
+        PaletteButton::Save,
+        PaletteButton::NextPalette,
+    ];
+
+    const FILE_BUTTONS: [PaletteButton; 2] = [PaletteButton::Import, PaletteButton::Export];
+
+    const REDUCE_BUTTONS: [PaletteButton; 1] = [PaletteButton::ReduceColors];
+
+    fn label(&self) -> &'static str {
+        match self {
+            PaletteButton::MoveLeft => "<",
+            PaletteButton::MoveRight => ">",
+            PaletteButton::Remove => "Remove",
+            PaletteButton::Save => "Save",
+            PaletteButton::NextPalette => "Next palette",
+            PaletteButton::Import => "Import",
+            PaletteButton::Export => "Export",
+            PaletteButton::ReduceColors => "Reduce colors",
+            PaletteButton::DecreaseMaterial(_) => "-",
+            PaletteButton::IncreaseMaterial(_) => "+",
+        }
+    }
+}
+
+fn toggle_palette_editor(
+    mut on_action: EventReader<OnEditorAction>,
+    mut palette_editor: ResMut<PaletteEditor>,
+) {
+    if action_triggered(&mut on_action, EditorAction::TogglePaletteEditor) {
+        palette_editor.is_open = !palette_editor.is_open;
+    }
+}
+
+fn update_editor_visibility(
+    palette_editor: Res<PaletteEditor>,
+    mut panels: Query<&mut Style, With<PaletteEditorPanel>>,
+) {
+    let display = if palette_editor.is_open {
+        Display::Flex
+    } else {
+        Display::None
+    };
+
+    for mut style in panels.iter_mut() {
+        if style.display != display {
+            style.display = display;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn button_interaction(
+    buttons: Query<(&PaletteButton, &Interaction), Changed<Interaction>>,
+    file_fields: Query<&TextField, With<PaletteFileField>>,
+    max_colors_fields: Query<&TextField, With<MaxColorsField>>,
+    mut chunks: Query<&mut Chunk>,
+    mut color_library: ResMut<ColorLibrary>,
+    mut recent_colors: ResMut<RecentColors>,
+    mut on_save_request: EventWriter<OnSavePaletteRequest>,
+    mut on_load_request: EventWriter<OnLoadPaletteRequest>,
+    mut on_import_request: EventWriter<OnImportPaletteRequest>,
+    mut on_export_request: EventWriter<OnExportPaletteRequest>,
+    mut on_quantize_request: EventWriter<OnQuantizePaletteRequest>,
+) {
+    for (button, interaction) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let selected_index = color_library.selected_index();
+
+        match button {
+            PaletteButton::MoveLeft => {
+                if selected_index > 0 {
+                    move_palette_color(
+                        &mut color_library,
+                        &mut chunks,
+                        &mut recent_colors,
+                        selected_index,
+                        selected_index - 1,
+                    );
+                }
+            }
+            PaletteButton::MoveRight => {
+                if selected_index + 1 < color_library.count() {
+                    move_palette_color(
+                        &mut color_library,
+                        &mut chunks,
+                        &mut recent_colors,
+                        selected_index,
+                        selected_index + 1,
+                    );
+                }
+            }
+            PaletteButton::Remove => {
+                remove_palette_color(
+                    &mut color_library,
+                    &mut chunks,
+                    &mut recent_colors,
+                    selected_index,
+                );
+            }
+            PaletteButton::Save => on_save_request.send(OnSavePaletteRequest),
+            PaletteButton::NextPalette => {
+                match next_palette_name(&saved_palette_names(), color_library.name()) {
+                    Some(name) => on_load_request.send(OnLoadPaletteRequest::new(name)),
+                    None => println!("No saved palettes found"),
+                }
+            }
+            PaletteButton::Import => {
+                for field in file_fields.iter() {
+                    on_import_request.send(OnImportPaletteRequest::new(field.text.trim()));
+                }
+            }
+            PaletteButton::Export => {
+                for field in file_fields.iter() {
+                    on_export_request.send(OnExportPaletteRequest::new(field.text.trim()));
+                }
+            }
+            PaletteButton::DecreaseMaterial(property) => {
+                step_material_property(&mut color_library, *property, -MATERIAL_STEP);
+            }
+            PaletteButton::IncreaseMaterial(property) => {
+                step_material_property(&mut color_library, *property, MATERIAL_STEP);
+            }
+            PaletteButton::ReduceColors => {
+                for field in max_colors_fields.iter() {
+                    match field.text.parse::<usize>() {
+                        Ok(max_colors) if max_colors > 0 => {
+                            on_quantize_request.send(OnQuantizePaletteRequest::new(max_colors))
+                        }
+                        _ => println!("Enter how many colors to keep"),
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn step_material_property(color_library: &mut ColorLibrary, property: MaterialProperty, step: f32) {
+    let selected_index = color_library.selected_index();
+
+    if let Some(palette_color) = color_library.palette_color(selected_index) {
+        let value = palette_color.material_property(property) + step;
+        color_library.set_material_property(selected_index, property, value);
+    }
+}
+
+/// Moves a palette entry and updates the blocks so they keep their color.
+fn move_palette_color(
+    color_library: &mut ColorLibrary,
+    chunks: &mut Query<&mut Chunk>,
+    recent_colors: &mut RecentColors,
+    from: usize,
+    to: usize,
+) {
+    color_library.move_color(from, to);
+    recent_colors.remap_indices(|index| Some(moved_index(index, from, to)));
+
+    for mut chunk in chunks.iter_mut() {
+        chunk.remap_palette_indices(|index| moved_index(index, from, to));
+    }
+}
+
+/// Removes a palette entry, entries that are still used by blocks can't be removed.
+fn remove_palette_color(
+    color_library: &mut ColorLibrary,
+    chunks: &mut Query<&mut Chunk>,
+    recent_colors: &mut RecentColors,
+    index: usize,
+) {
+    let used_count: usize = chunks
+        .iter()
+        .map(|chunk| chunk.count_blocks_with_palette_index(index))
+        .sum();
+
+    if used_count > 0 {
+        println!(
+            "Can't remove a color that {} blocks still use, replace it first",
+            used_count
+        );
+        return;
+    }
+
+    color_library.remove_color(index);
+    recent_colors
+        .remap_indices(|recent| (recent != index).then(|| index_after_removal(recent, index)));
+
+    for mut chunk in chunks.iter_mut() {
+        chunk.remap_palette_indices(|block_index| index_after_removal(block_index, index));
+    }
+}
+
+fn apply_submitted_names(
+    mut on_submitted: EventReader<OnTextFieldSubmitted>,
+    palette_name_fields: Query<(), With<PaletteNameField>>,
+    color_name_fields: Query<(), With<ColorNameField>>,
+    mut color_library: ResMut<ColorLibrary>,
+) {
+    for submitted in on_submitted.iter() {
+        let name = submitted.text.trim();
+
+        if palette_name_fields.contains(submitted.field) {
+            color_library.set_name(name);
+        } else if color_name_fields.contains(submitted.field) {
+            let selected_index = color_library.selected_index();
+            color_library.rename_color(selected_index, name);
+        }
+    }
+}
+
+fn update_material_texts(
+    color_library: Res<ColorLibrary>,
+    mut texts: Query<(&MaterialValueText, &mut Text)>,
+) {
+    let selected_color = color_library.palette_color(color_library.selected_index());
+
+    for (value_text, mut text) in texts.iter_mut() {
+        text.sections[0].value = material_text(value_text.0, selected_color);
+    }
+}
+
+fn material_text(property: MaterialProperty, palette_color: Option<&PaletteColor>) -> String {
+    match palette_color {
+        Some(palette_color) => format!(
+            "{}: {:.1}",
+            property.label(),
+            palette_color.material_property(property)
+        ),
+        None => format!("{}: -", property.label()),
+    }
+}
+
+fn update_name_fields(
+    color_library: Res<ColorLibrary>,
+    mut palette_name_fields: Query<
+        &mut TextField,
+        (With<PaletteNameField>, Without<ColorNameField>),
+    >,
+    mut color_name_fields: Query<&mut TextField, With<ColorNameField>>,
+) {
+    for mut field in palette_name_fields
+        .iter_mut()
+        .filter(|field| !field.is_focused())
+    {
+        field.text = color_library.name().to_string();
+    }
+
+    let color_name = color_library
+        .color_name(color_library.selected_index())
+        .unwrap_or_default();
+
+    for mut field in color_name_fields
+        .iter_mut()
+        .filter(|field| !field.is_focused())
+    {
+        field.text = color_name.to_string();
+    }
+}
+
+// UI
+
+fn spawn_palette_editor(mut commands: Commands, color_library: Res<ColorLibrary>) {
+    commands
+        .spawn((
+            Name::new("Palette editor panel"),
+            PaletteEditorPanel,
+            Interaction::default(),
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(100.0),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(10.0)),
+                    row_gap: Val::Px(6.0),
+                    ..default()
+                },
+                background_color: PANEL_COLOR.into(),
+                ..default()
+            },
+        ))
+        .with_children(|panel| {
+            panel.spawn(TextBundle::from_section("Palette", text_field_style()));
+            spawn_text_field(
+                panel,
+                TextField::new(color_library.name(), MAX_NAME_LENGTH, accepts_any),
+                Val::Px(200.0),
+                PaletteNameField,
+            );
+
+            panel.spawn(TextBundle::from_section(
+                "Selected color",
+                text_field_style(),
+            ));
+            spawn_text_field(
+                panel,
+                TextField::new(
+                    color_library
+                        .color_name(color_library.selected_index())
+                        .unwrap_or_default(),
+                    MAX_NAME_LENGTH,
+                    accepts_any,
+                ),
+                Val::Px(200.0),
+                ColorNameField,
+            );
+
+            panel
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(6.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    for button in PaletteButton::EDIT_BUTTONS {
+                        spawn_palette_button(row, button);
+                    }
+                });
+
+            let selected_color = color_library.palette_color(color_library.selected_index());
+
+            for property in MaterialProperty::ALL {
+                panel
+                    .spawn(NodeBundle {
+                        style: Style {
+                            column_gap: Val::Px(6.0),
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        spawn_palette_button(row, PaletteButton::DecreaseMaterial(property));
+                        spawn_palette_button(row, PaletteButton::IncreaseMaterial(property));
+                        row.spawn((
+                            MaterialValueText(property),
+                            TextBundle::from_section(
+                                material_text(property, selected_color),
+                                text_field_style(),
+                            ),
+                        ));
+                    });
+            }
+
+            panel.spawn(TextBundle::from_section(
+                "Import / export file (.gpl, .hex, .txt, .png)",
+                text_field_style(),
+            ));
+            spawn_text_field(
+                panel,
+                TextField::new("assets/palettes/", MAX_PATH_LENGTH, accepts_any),
+                Val::Px(300.0),
+                PaletteFileField,
+            );
+
+            panel
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(6.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    for button in PaletteButton::FILE_BUTTONS {
+                        spawn_palette_button(row, button);
+                    }
+                });
+
+            panel
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(6.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn(TextBundle::from_section("Max colors", text_field_style()));
+                    spawn_text_field(
+                        row,
+                        TextField::new(DEFAULT_MAX_COLORS, 3, |character| {
+                            character.is_ascii_digit()
+                        }),
+                        Val::Px(50.0),
+                        MaxColorsField,
+                    );
+
+                    for button in PaletteButton::REDUCE_BUTTONS {
+                        spawn_palette_button(row, button);
+                    }
+                });
+        });
+}
+
+fn spawn_palette_button(row: &mut ChildBuilder, button: PaletteButton) {
+    row.spawn((
+        Name::new("Palette button"),
+        button,
+        ButtonBundle {
+            style: Style {
+                padding: UiRect::all(Val::Px(4.0)),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            background_color: BUTTON_COLOR.into(),
+            ..default()
+        },
+    ))
+    .with_children(|node| {
+        node.spawn(TextBundle::from_section(button.label(), text_field_style()));
+    });
+}
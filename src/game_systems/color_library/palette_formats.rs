@@ -0,0 +1,534 @@
+use std::{fs, io, path::Path};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::{CompressedImageFormats, ImageType},
+    },
+};
+
+use crate::world::chunk::Chunk;
+
+use super::{remap_to_palette, ColorLibrary, PaletteColor};
+
+pub struct PaletteFormatsPlugin;
+
+impl Plugin for PaletteFormatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OnImportPaletteRequest>()
+            .add_event::<OnExportPaletteRequest>()
+            .add_systems(Update, (handle_import_requests, handle_export_requests));
+    }
+}
+
+/// Replaces the current palette with the one in the given file, the format is picked from the file extension.
+#[derive(Event)]
+pub struct OnImportPaletteRequest {
+    pub path: String,
+}
+
+impl OnImportPaletteRequest {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+/// Writes the current palette to the given file, the format is picked from the file extension.
+#[derive(Event)]
+pub struct OnExportPaletteRequest {
+    pub path: String,
+}
+
+impl OnExportPaletteRequest {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[derive(Debug)]
+pub enum PaletteFormatError {
+    UnknownFormat(String),
+    Io(io::Error),
+    Parse { line: usize, message: String },
+    Image(String),
+    NoColors,
+    TooManyColors(usize),
+}
+
+impl From<io::Error> for PaletteFormatError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteFormat {
+    /// GIMP palette, `.gpl`.
+    Gimp,
+    /// One `RRGGBB` value per line, `.hex`.
+    Hex,
+    /// Paint.NET palette, one `AARRGGBB` value per line, `.txt`.
+    PaintNet,
+    /// An image with one pixel per color, `.png`.
+    Png,
+}
+
+impl PaletteFormat {
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = Path::new(path).extension()?.to_str()?.to_lowercase();
+
+        match extension.as_str() {
+            "gpl" => Some(PaletteFormat::Gimp),
+            "hex" => Some(PaletteFormat::Hex),
+            "txt" => Some(PaletteFormat::PaintNet),
+            "png" => Some(PaletteFormat::Png),
+            _ => None,
+        }
+    }
+}
+
+fn handle_import_requests(
+    mut color_library: ResMut<ColorLibrary>,
+    mut chunks: Query<&mut Chunk>,
+    mut on_import_request: EventReader<OnImportPaletteRequest>,
+) {
+    for request in on_import_request.iter() {
+        match import_palette(&request.path) {
+            Ok(palette) => {
+                println!(
+                    "Imported {} colors from '{}'",
+                    palette.count(),
+                    request.path
+                );
+
+                for mut chunk in chunks.iter_mut() {
+                    remap_to_palette(&mut chunk, &color_library, &palette);
+                }
+
+                *color_library = palette;
+            }
+            Err(error) => println!("Error while importing palette: {:?}", error),
+        }
+    }
+}
+
+fn handle_export_requests(
+    color_library: Res<ColorLibrary>,
+    mut on_export_request: EventReader<OnExportPaletteRequest>,
+) {
+    for request in on_export_request.iter() {
+        match export_palette(&color_library, &request.path) {
+            Ok(()) => println!("Exported palette to '{}'", request.path),
+            Err(error) => println!("Error while exporting palette: {:?}", error),
+        }
+    }
+}
+
+pub fn import_palette(path: &str) -> Result<ColorLibrary, PaletteFormatError> {
+    let format = PaletteFormat::from_path(path)
+        .ok_or_else(|| PaletteFormatError::UnknownFormat(path.to_string()))?;
+    let file_name = palette_name_from_path(path);
+
+    let palette = match format {
+        PaletteFormat::Gimp => parse_gpl(&fs::read_to_string(path)?, &file_name),
+        PaletteFormat::Hex => parse_hex(&fs::read_to_string(path)?, &file_name),
+        PaletteFormat::PaintNet => parse_paint_net(&fs::read_to_string(path)?, &file_name),
+        PaletteFormat::Png => parse_png(&fs::read(path)?, &file_name),
+    }?;
+
+    check_color_count(palette)
+}
+
+/// An empty palette would leave every block without a color,
+/// and blocks can only point at the first `ColorLibrary::MAX_COLORS` entries.
+fn check_color_count(palette: ColorLibrary) -> Result<ColorLibrary, PaletteFormatError> {
+    if palette.count() == 0 {
+        return Err(PaletteFormatError::NoColors);
+    }
+
+    if palette.count() > ColorLibrary::MAX_COLORS {
+        return Err(PaletteFormatError::TooManyColors(palette.count()));
+    }
+
+    Ok(palette)
+}
+
+pub fn export_palette(palette: &ColorLibrary, path: &str) -> Result<(), PaletteFormatError> {
+    let format = PaletteFormat::from_path(path)
+        .ok_or_else(|| PaletteFormatError::UnknownFormat(path.to_string()))?;
+
+    let serialized = match format {
+        PaletteFormat::Gimp => write_gpl(palette),
+        PaletteFormat::Hex => write_hex(palette),
+        PaletteFormat::PaintNet => write_paint_net(palette),
+        PaletteFormat::Png => return write_png(palette, path),
+    };
+
+    Ok(fs::write(path, serialized)?)
+}
+
+fn palette_name_from_path(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+// GIMP
+
+fn parse_gpl(file: &str, default_name: &str) -> Result<ColorLibrary, PaletteFormatError> {
+    let mut lines = file.lines().enumerate();
+
+    match lines.next() {
+        Some((_, header)) if header.trim() == "GIMP Palette" => {}
+        _ => return Err(parse_error(0, "missing 'GIMP Palette' header")),
+    }
+
+    let mut name = default_name.to_string();
+    let mut colors = Vec::new();
+
+    for (index, line) in lines {
+        let line = line.trim();
+
+        if let Some(palette_name) = line.strip_prefix("Name:") {
+            name = palette_name.trim().to_string();
+            continue;
+        }
+
+        if line.is_empty() || line.starts_with('#') || line.starts_with("Columns:") {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let mut channel = || {
+            parts
+                .next()
+                .and_then(|part| part.parse::<u8>().ok())
+                .ok_or_else(|| parse_error(index, "expected 3 color values from 0 to 255"))
+        };
+
+        let color = Color::rgb_u8(channel()?, channel()?, channel()?);
+        let color_name = parts.collect::<Vec<&str>>().join(" ");
+
+        colors.push(PaletteColor::with_name(color, color_name));
+    }
+
+    Ok(ColorLibrary::from_colors(name, colors))
+}
+
+fn write_gpl(palette: &ColorLibrary) -> String {
+    let mut file = format!("GIMP Palette\nName: {}\n#\n", palette.name());
+
+    for palette_color in palette.palette_colors() {
+        let [r, g, b, _] = palette_color.color.as_rgba_u8();
+        let name = if palette_color.name.is_empty() {
+            "Untitled"
+        } else {
+            &palette_color.name
+        };
+
+        file.push_str(&format!("{:>3} {:>3} {:>3}\t{}\n", r, g, b, name));
+    }
+
+    file
+}
+
+// Hex list
+
+fn parse_hex(file: &str, name: &str) -> Result<ColorLibrary, PaletteFormatError> {
+    let colors = file
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            Color::hex(line.trim())
+                .map(PaletteColor::new)
+                .map_err(|_| parse_error(index, "expected a RRGGBB color"))
+        })
+        .collect::<Result<Vec<PaletteColor>, PaletteFormatError>>()?;
+
+    Ok(ColorLibrary::from_colors(name, colors))
+}
+
+fn write_hex(palette: &ColorLibrary) -> String {
+    palette
+        .all_colors()
+        .into_iter()
+        .map(|color| {
+            let [r, g, b, _] = color.as_rgba_u8();
+            format!("{:02x}{:02x}{:02x}\n", r, g, b)
+        })
+        .collect()
+}
+
+// Paint.NET
+
+fn parse_paint_net(file: &str, default_name: &str) -> Result<ColorLibrary, PaletteFormatError> {
+    let mut name = default_name.to_string();
+    let mut colors = Vec::new();
+
+    for (index, line) in file.lines().enumerate() {
+        let line = line.trim();
+
+        if let Some(comment) = line.strip_prefix(';') {
+            if let Some(palette_name) = comment.trim().strip_prefix("Palette Name:") {
+                name = palette_name.trim().to_string();
+            }
+            continue;
+        }
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let value = u32::from_str_radix(line, 16)
+            .ok()
+            .filter(|_| line.len() == 8)
+            .ok_or_else(|| parse_error(index, "expected an AARRGGBB color"))?;
+        let [a, r, g, b] = value.to_be_bytes();
+
+        colors.push(PaletteColor::new(Color::rgba_u8(r, g, b, a)));
+    }
+
+    Ok(ColorLibrary::from_colors(name, colors))
+}
+
+fn write_paint_net(palette: &ColorLibrary) -> String {
+    let mut file = format!(
+        "; paint.net Palette File\n; Palette Name: {}\n; Colors: {}\n",
+        palette.name(),
+        palette.count()
+    );
+
+    for color in palette.all_colors() {
+        let [r, g, b, a] = color.as_rgba_u8();
+        file.push_str(&format!("{:02X}{:02X}{:02X}{:02X}\n", a, r, g, b));
+    }
+
+    file
+}
+
+// PNG
+
+fn parse_png(bytes: &[u8], name: &str) -> Result<ColorLibrary, PaletteFormatError> {
+    let image = Image::from_buffer(
+        bytes,
+        ImageType::Extension("png"),
+        CompressedImageFormats::NONE,
+        true,
+    )
+    .map_err(|error| PaletteFormatError::Image(format!("{:?}", error)))?;
+
+    let pixels = image
+        .try_into_dynamic()
+        .map_err(|error| PaletteFormatError::Image(error.to_string()))?
+        .to_rgba8()
+        .into_raw();
+
+    let colors = colors_from_rgba_pixels(&pixels)
+        .into_iter()
+        .map(PaletteColor::new)
+        .collect();
+
+    Ok(ColorLibrary::from_colors(name, colors))
+}
+
+fn write_png(palette: &ColorLibrary, path: &str) -> Result<(), PaletteFormatError> {
+    let colors = palette.all_colors();
+
+    let image = Image::new(
+        Extent3d {
+            width: colors.len() as u32,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        colors_to_rgba_pixels(&colors),
+        TextureFormat::Rgba8UnormSrgb,
+    );
+
+    image
+        .try_into_dynamic()
+        .map_err(|error| PaletteFormatError::Image(error.to_string()))?
+        .save(path)
+        .map_err(|error| PaletteFormatError::Image(error.to_string()))
+}
+
+/// Reads one color per pixel, fully transparent pixels are skipped.
+fn colors_from_rgba_pixels(pixels: &[u8]) -> Vec<Color> {
+    pixels
+        .chunks_exact(4)
+        .filter(|pixel| pixel[3] > 0)
+        .map(|pixel| Color::rgba_u8(pixel[0], pixel[1], pixel[2], pixel[3]))
+        .collect()
+}
+
+fn colors_to_rgba_pixels(colors: &[Color]) -> Vec<u8> {
+    colors.iter().flat_map(|color| color.as_rgba_u8()).collect()
+}
+
+fn parse_error(index: usize, message: &str) -> PaletteFormatError {
+    PaletteFormatError::Parse {
+        line: index + 1,
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_format_from_extension() {
+        assert_eq!(
+            PaletteFormat::from_path("palettes/forest.GPL"),
+            Some(PaletteFormat::Gimp)
+        );
+        assert_eq!(
+            PaletteFormat::from_path("endesga-32.hex"),
+            Some(PaletteFormat::Hex)
+        );
+        assert_eq!(
+            PaletteFormat::from_path("a.txt"),
+            Some(PaletteFormat::PaintNet)
+        );
+        assert_eq!(PaletteFormat::from_path("a.png"), Some(PaletteFormat::Png));
+        assert_eq!(PaletteFormat::from_path("a.ron"), None);
+        assert_eq!(PaletteFormat::from_path("no_extension"), None);
+    }
+
+    #[test]
+    fn can_parse_gpl() {
+        let file =
+            "GIMP Palette\nName: Forest\nColumns: 4\n#\n255   0   0\tRed\n  0 128 255\tSky blue\n";
+
+        let palette = parse_gpl(file, "forest").unwrap();
+
+        assert_eq!(palette.name(), "Forest");
+        assert_eq!(
+            palette.all_colors(),
+            vec![Color::rgb_u8(255, 0, 0), Color::rgb_u8(0, 128, 255)]
+        );
+        assert_eq!(palette.color_name(1), Some("Sky blue"));
+    }
+
+    #[test]
+    fn gpl_needs_header_and_valid_values() {
+        assert!(parse_gpl("255 0 0\n", "a").is_err());
+
+        let error = parse_gpl("GIMP Palette\n255 300 0\n", "a").unwrap_err();
+
+        assert!(matches!(error, PaletteFormatError::Parse { line: 2, .. }));
+    }
+
+    #[test]
+    fn gpl_round_trips() {
+        let palette = create_palette();
+
+        let parsed = parse_gpl(&write_gpl(&palette), "other").unwrap();
+
+        assert_eq!(parsed.name(), "test");
+        assert_eq!(parsed.all_colors(), palette.all_colors());
+        assert_eq!(parsed.color_name(0), Some("grass"));
+        assert_eq!(parsed.color_name(1), Some("Untitled"));
+    }
+
+    #[test]
+    fn can_parse_hex_list() {
+        let palette = parse_hex("ff0000\r\n\n00FF80\n", "lospec").unwrap();
+
+        assert_eq!(palette.name(), "lospec");
+        assert_eq!(
+            palette.all_colors(),
+            vec![Color::rgb_u8(255, 0, 0), Color::rgb_u8(0, 255, 128)]
+        );
+        assert!(parse_hex("ff0000\nnope\n", "a").is_err());
+    }
+
+    #[test]
+    fn palettes_without_colors_are_not_imported() {
+        let path = std::env::temp_dir().join("voxel_editor_empty_palette.hex");
+        fs::write(&path, "\n\n").unwrap();
+
+        let result = import_palette(path.to_str().unwrap());
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(PaletteFormatError::NoColors)));
+        assert!(matches!(
+            parse_gpl("GIMP Palette\nName: Empty\n", "a").and_then(check_color_count),
+            Err(PaletteFormatError::NoColors)
+        ));
+        assert!(matches!(
+            parse_paint_net("; paint.net Palette File\n", "a").and_then(check_color_count),
+            Err(PaletteFormatError::NoColors)
+        ));
+    }
+
+    #[test]
+    fn hex_list_round_trips() {
+        let palette = create_palette();
+
+        let parsed = parse_hex(&write_hex(&palette), "test").unwrap();
+
+        assert_eq!(parsed.all_colors(), palette.all_colors());
+    }
+
+    #[test]
+    fn can_parse_paint_net() {
+        let file = "; paint.net Palette File\n; Palette Name: Sunset\nFFFF0000\n8000FF00\n";
+
+        let palette = parse_paint_net(file, "file").unwrap();
+
+        assert_eq!(palette.name(), "Sunset");
+        assert_eq!(
+            palette.all_colors(),
+            vec![
+                Color::rgba_u8(255, 0, 0, 255),
+                Color::rgba_u8(0, 255, 0, 128)
+            ]
+        );
+        assert!(parse_paint_net("FF0000\n", "a").is_err());
+    }
+
+    #[test]
+    fn paint_net_round_trips() {
+        let palette = create_palette();
+
+        let parsed = parse_paint_net(&write_paint_net(&palette), "other").unwrap();
+
+        assert_eq!(parsed.name(), "test");
+        assert_eq!(parsed.all_colors(), palette.all_colors());
+    }
+
+    #[test]
+    fn png_pixels_round_trip() {
+        let colors = vec![Color::rgb_u8(255, 0, 0), Color::rgba_u8(10, 20, 30, 40)];
+
+        let pixels = colors_to_rgba_pixels(&colors);
+
+        assert_eq!(pixels, vec![255, 0, 0, 255, 10, 20, 30, 40]);
+        assert_eq!(colors_from_rgba_pixels(&pixels), colors);
+    }
+
+    #[test]
+    fn transparent_pixels_are_skipped() {
+        let pixels = [255, 0, 0, 255, 0, 0, 0, 0, 0, 0, 255, 255];
+
+        assert_eq!(
+            colors_from_rgba_pixels(&pixels),
+            vec![Color::rgb_u8(255, 0, 0), Color::rgb_u8(0, 0, 255)]
+        );
+    }
+
+    fn create_palette() -> ColorLibrary {
+        ColorLibrary::from_colors(
+            "test",
+            vec![
+                PaletteColor::with_name(Color::rgb_u8(12, 200, 40), "grass"),
+                PaletteColor::new(Color::rgb_u8(0, 0, 0)),
+                PaletteColor::new(Color::rgb_u8(255, 255, 255)),
+            ],
+        )
+    }
+}
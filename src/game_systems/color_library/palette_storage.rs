@@ -0,0 +1,205 @@
+use std::{fs, path::Path};
+
+use bevy::prelude::*;
+use ron::Error;
+
+use crate::world::chunk::Chunk;
+
+use super::{remap_to_palette, ColorLibrary};
+
+pub struct PaletteStoragePlugin;
+
+impl Plugin for PaletteStoragePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OnSavePaletteRequest>()
+            .add_event::<OnLoadPaletteRequest>()
+            .add_systems(Startup, load_default_palette)
+            .add_systems(Update, (handle_save_requests, handle_load_requests));
+    }
+}
+
+const PALETTES_DIRECTORY: &str = "assets/palettes";
+const DEFAULT_PALETTE_NAME: &str = "default";
+
+/// Saves the current palette to disk, using the palette name as file name.
+#[derive(Event)]
+pub struct OnSavePaletteRequest;
+
+#[derive(Event)]
+pub struct OnLoadPaletteRequest {
+    pub name: String,
+}
+
+impl OnLoadPaletteRequest {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+fn load_default_palette(mut color_library: ResMut<ColorLibrary>) {
+    let path = palette_path(DEFAULT_PALETTE_NAME);
+
+    if !Path::new(&path).exists() {
+        return;
+    }
+
+    match load_palette_from_file(&path) {
+        Ok(palette) => *color_library = palette,
+        Err(error) => println!("Error while loading default palette: {:?}", error),
+    }
+}
+
+fn handle_save_requests(
+    color_library: Res<ColorLibrary>,
+    on_save_request: EventReader<OnSavePaletteRequest>,
+) {
+    if on_save_request.is_empty() {
+        return;
+    }
+
+    match save_palette_to_file(&color_library, &palette_path(color_library.name())) {
+        Ok(()) => println!("Saved palette '{}'", color_library.name()),
+        Err(error) => println!("Error while saving palette: {:?}", error),
+    }
+}
+
+fn handle_load_requests(
+    mut color_library: ResMut<ColorLibrary>,
+    mut chunks: Query<&mut Chunk>,
+    mut on_load_request: EventReader<OnLoadPaletteRequest>,
+) {
+    for request in on_load_request.iter() {
+        match load_palette_from_file(&palette_path(&request.name)) {
+            Ok(palette) => {
+                for mut chunk in chunks.iter_mut() {
+                    remap_to_palette(&mut chunk, &color_library, &palette);
+                }
+
+                *color_library = palette;
+            }
+            Err(error) => println!("Error while loading palette: {:?}", error),
+        }
+    }
+}
+
+/// Returns the names of all palettes saved on disk, sorted alphabetically.
+pub fn saved_palette_names() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(PALETTES_DIRECTORY) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| palette_name_from_file_name(&entry.file_name().to_string_lossy()))
+        .collect();
+
+    names.sort();
+    names
+}
+
+/// Returns the saved palette after the current one in the list, looping back to the first.
+pub fn next_palette_name(names: &[String], current: &str) -> Option<String> {
+    let current = sanitize_file_name(current);
+    let next_index = names
+        .iter()
+        .position(|name| *name == current)
+        .map_or(0, |index| (index + 1) % names.len());
+
+    names.get(next_index).cloned()
+}
+
+fn palette_name_from_file_name(file_name: &str) -> Option<String> {
+    file_name
+        .strip_suffix(".ron")
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_string())
+}
+
+fn palette_path(name: &str) -> String {
+    format!("{}/{}.ron", PALETTES_DIRECTORY, sanitize_file_name(name))
+}
+
+/// Replaces characters that aren't safe in file names.
+pub fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .trim()
+        .chars()
+        .map(|character| {
+            if character.is_alphanumeric() || character == '-' || character == '_' {
+                character
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if sanitized.is_empty() {
+        DEFAULT_PALETTE_NAME.to_string()
+    } else {
+        sanitized
+    }
+}
+
+fn save_palette_to_file(palette: &ColorLibrary, path: &str) -> Result<(), Error> {
+    fs::create_dir_all(PALETTES_DIRECTORY)?;
+
+    let serialized = ron::to_string(palette)?;
+    Ok(fs::write(path, serialized)?)
+}
+
+fn load_palette_from_file(path: &str) -> Result<ColorLibrary, Error> {
+    let file = fs::read_to_string(path)?;
+    let palette = ron::from_str::<ColorLibrary>(&file)?;
+
+    if palette.count() > ColorLibrary::MAX_COLORS {
+        return Err(Error::Message(format!(
+            "the palette has more than {} colors",
+            ColorLibrary::MAX_COLORS
+        )));
+    }
+
+    Ok(palette)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_names_are_safe_file_names() {
+        assert_eq!(sanitize_file_name("forest"), "forest");
+        assert_eq!(sanitize_file_name(" my palette/2 "), "my_palette_2");
+        assert_eq!(sanitize_file_name(""), "default");
+    }
+
+    #[test]
+    fn only_ron_files_are_palettes() {
+        assert_eq!(
+            palette_name_from_file_name("forest.ron"),
+            Some("forest".to_string())
+        );
+        assert_eq!(palette_name_from_file_name("notes.txt"), None);
+        assert_eq!(palette_name_from_file_name(".ron"), None);
+    }
+
+    #[test]
+    fn next_palette_loops_around() {
+        let names = vec!["a".to_string(), "b".to_string()];
+
+        assert_eq!(next_palette_name(&names, "a"), Some("b".to_string()));
+        assert_eq!(next_palette_name(&names, " b"), Some("a".to_string()));
+        assert_eq!(next_palette_name(&names, "unsaved"), Some("a".to_string()));
+        assert_eq!(next_palette_name(&[], "a"), None);
+    }
+
+    #[test]
+    fn palette_survives_serialization() {
+        let mut palette = ColorLibrary::with_default_colors();
+        palette.set_name("test");
+        palette.rename_color(0, "grass");
+
+        let serialized = ron::to_string(&palette).unwrap();
+
+        assert_eq!(ron::from_str::<ColorLibrary>(&serialized).unwrap(), palette);
+    }
+}
@@ -0,0 +1,407 @@
+use bevy::prelude::*;
+
+use crate::world::chunk::Chunk;
+
+use super::{
+    color_harmony::{color_to_oklab, oklab_to_color},
+    ColorLibrary, PaletteColor,
+};
+
+pub struct QuantizePlugin;
+
+impl Plugin for QuantizePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OnQuantizePaletteRequest>()
+            .add_systems(Update, handle_quantize_requests);
+    }
+}
+
+/// Reduces the colors used in the scene to at most `max_colors`, the palette is replaced with the result.
+#[derive(Event)]
+pub struct OnQuantizePaletteRequest {
+    pub max_colors: usize,
+}
+
+impl OnQuantizePaletteRequest {
+    pub fn new(max_colors: usize) -> Self {
+        Self { max_colors }
+    }
+}
+
+fn handle_quantize_requests(
+    mut quantize_events: EventReader<OnQuantizePaletteRequest>,
+    mut color_library: ResMut<ColorLibrary>,
+    mut chunks: Query<&mut Chunk>,
+) {
+    for event in quantize_events.iter() {
+        let used_colors = count_used_colors(&color_library, chunks.iter());
+
+        if used_colors.is_empty() || event.max_colors == 0 {
+            continue;
+        }
+
+        let weighted_colors: Vec<(Color, usize)> = used_colors
+            .iter()
+            .map(|used| (used.color, used.block_count))
+            .collect();
+
+        let (reduced_colors, mapping) = median_cut(&weighted_colors, event.max_colors);
+        let palette_mapping = map_palette_indices(&color_library, &used_colors, &mapping);
+
+        for mut chunk in chunks.iter_mut() {
+            remap_to_reduced_palette(&mut chunk, &palette_mapping);
+        }
+
+        println!(
+            "Reduced {} colors to {}",
+            used_colors.len(),
+            reduced_colors.len()
+        );
+
+        let palette_colors =
+            reduced_palette_colors(&color_library, &used_colors, &reduced_colors, &mapping);
+
+        *color_library = ColorLibrary::from_colors(color_library.name(), palette_colors);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct UsedColor {
+    palette_index: usize,
+    color: Color,
+    block_count: usize,
+}
+
+/// Returns every palette entry that is used by blocks, with how many blocks use it.
+/// Entries that share a color stay separate so their names and materials aren't lost.
+fn count_used_colors<'a>(
+    color_library: &ColorLibrary,
+    chunks: impl Iterator<Item = &'a Chunk>,
+) -> Vec<UsedColor> {
+    let mut block_counts = vec![0; color_library.count()];
+
+    for chunk in chunks {
+        for palette_index in chunk.palette_indices() {
+            if let Some(count) = block_counts.get_mut(palette_index) {
+                *count += 1;
+            }
+        }
+    }
+
+    color_library
+        .all_colors()
+        .into_iter()
+        .zip(block_counts)
+        .enumerate()
+        .filter(|(_, (_, block_count))| *block_count > 0)
+        .map(|(palette_index, (color, block_count))| UsedColor {
+            palette_index,
+            color,
+            block_count,
+        })
+        .collect()
+}
+
+/// Maps every old palette index to its index in the reduced palette, unused entries map to None.
+fn map_palette_indices(
+    color_library: &ColorLibrary,
+    used_colors: &[UsedColor],
+    mapping: &[usize],
+) -> Vec<Option<usize>> {
+    let mut palette_mapping = vec![None; color_library.count()];
+
+    for (used, reduced_index) in used_colors.iter().zip(mapping) {
+        palette_mapping[used.palette_index] = Some(*reduced_index);
+    }
+
+    palette_mapping
+}
+
+/// Builds the reduced palette entries, each one keeps the name, transparency and material
+/// of the most used entry that was merged into it.
+fn reduced_palette_colors(
+    color_library: &ColorLibrary,
+    used_colors: &[UsedColor],
+    reduced_colors: &[Color],
+    mapping: &[usize],
+) -> Vec<PaletteColor> {
+    reduced_colors
+        .iter()
+        .enumerate()
+        .map(|(reduced_index, reduced_color)| {
+            let representative = used_colors
+                .iter()
+                .zip(mapping)
+                .filter(|(_, mapped_index)| **mapped_index == reduced_index)
+                .max_by_key(|(used, _)| used.block_count)
+                .and_then(|(used, _)| color_library.palette_colors().get(used.palette_index));
+
+            match representative {
+                Some(entry) => PaletteColor {
+                    color: reduced_color.with_a(entry.color.a()),
+                    ..entry.clone()
+                },
+                None => PaletteColor::new(*reduced_color),
+            }
+        })
+        .collect()
+}
+
+/// Blocks with an index that isn't in the old palette get the first reduced color.
+fn remap_to_reduced_palette(chunk: &mut Chunk, palette_mapping: &[Option<usize>]) {
+    chunk.remap_palette_indices(|index| palette_mapping.get(index).copied().flatten().unwrap_or(0));
+}
+
+/// Reduces weighted colors to at most `max_colors` with median cut in OKLab.
+/// Returns the reduced colors and for every input color the index of the reduced color it maps to.
+pub fn median_cut(colors: &[(Color, usize)], max_colors: usize) -> (Vec<Color>, Vec<usize>) {
+    let points: Vec<Vec3> = colors
+        .iter()
+        .map(|(color, _)| color_to_oklab(*color))
+        .collect();
+
+    let mut boxes: Vec<Vec<usize>> = vec![(0..colors.len()).collect()];
+
+    while boxes.len() < max_colors {
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, color_box)| color_box.len() > 1)
+            .map(|(index, color_box)| (index, box_extent(color_box, &points)))
+            .filter(|(_, extent)| extent.max_element() > 0.0)
+            .max_by(|(_, a), (_, b)| a.max_element().total_cmp(&b.max_element()));
+
+        let Some((box_index, extent)) = widest else {
+            break;
+        };
+
+        let color_box = boxes.swap_remove(box_index);
+        let (first, second) = split_box(color_box, &points, colors, widest_axis(extent));
+
+        boxes.push(first);
+        boxes.push(second);
+    }
+
+    let mut mapping = vec![0; colors.len()];
+    let mut reduced_colors = Vec::new();
+
+    for (box_index, color_box) in boxes.iter().enumerate() {
+        reduced_colors.push(average_color(color_box, &points, colors));
+
+        for color_index in color_box.iter() {
+            mapping[*color_index] = box_index;
+        }
+    }
+
+    (reduced_colors, mapping)
+}
+
+fn box_extent(color_box: &[usize], points: &[Vec3]) -> Vec3 {
+    let (min, max) = color_box.iter().fold(
+        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        |(min, max), index| (min.min(points[*index]), max.max(points[*index])),
+    );
+
+    max - min
+}
+
+fn widest_axis(extent: Vec3) -> usize {
+    if extent.x >= extent.y && extent.x >= extent.z {
+        0
+    } else if extent.y >= extent.z {
+        1
+    } else {
+        2
+    }
+}
+
+/// Splits the box along the axis at the weighted median, both halves keep at least one color.
+fn split_box(
+    mut color_box: Vec<usize>,
+    points: &[Vec3],
+    colors: &[(Color, usize)],
+    axis: usize,
+) -> (Vec<usize>, Vec<usize>) {
+    color_box.sort_by(|a, b| points[*a][axis].total_cmp(&points[*b][axis]));
+
+    let total_weight: usize = color_box.iter().map(|index| colors[*index].1).sum();
+    let mut weight = 0;
+    let mut split_at = 1;
+
+    for (position, index) in color_box.iter().enumerate() {
+        weight += colors[*index].1;
+
+        if weight * 2 >= total_weight {
+            split_at = position + 1;
+            break;
+        }
+    }
+
+    let split_at = split_at.clamp(1, color_box.len() - 1);
+    let second = color_box.split_off(split_at);
+
+    (color_box, second)
+}
+
+fn average_color(color_box: &[usize], points: &[Vec3], colors: &[(Color, usize)]) -> Color {
+    let total_weight: usize = color_box.iter().map(|index| colors[*index].1).sum();
+    let weighted_sum = color_box.iter().fold(Vec3::ZERO, |sum, index| {
+        sum + points[*index] * colors[*index].1 as f32
+    });
+
+    oklab_to_color(weighted_sum / total_weight.max(1) as f32, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        game_systems::color_library::colors_match, newtypes::coordinate::Coordinate,
+        world::block::Block,
+    };
+
+    use super::*;
+
+    #[test]
+    fn near_duplicates_are_merged() {
+        let colors = vec![
+            (Color::rgb(1.0, 0.0, 0.0), 10),
+            (Color::rgb(0.98, 0.02, 0.0), 10),
+            (Color::rgb(0.0, 0.0, 1.0), 10),
+            (Color::rgb(0.02, 0.0, 0.97), 10),
+        ];
+
+        let (reduced, mapping) = median_cut(&colors, 2);
+
+        assert_eq!(reduced.len(), 2);
+        assert_eq!(mapping[0], mapping[1]);
+        assert_eq!(mapping[2], mapping[3]);
+        assert_ne!(mapping[0], mapping[2]);
+        assert!(colors_match(reduced[mapping[0]], Color::RED, 0.05));
+    }
+
+    #[test]
+    fn keeps_colors_when_under_the_limit() {
+        let colors = vec![(Color::RED, 1), (Color::GREEN, 1)];
+
+        let (reduced, mapping) = median_cut(&colors, 5);
+
+        assert_eq!(reduced.len(), 2);
+        assert_ne!(mapping[0], mapping[1]);
+    }
+
+    #[test]
+    fn heavier_colors_pull_the_average() {
+        let colors = vec![(Color::BLACK, 9), (Color::rgb(0.1, 0.1, 0.1), 1)];
+
+        let (reduced, _) = median_cut(&colors, 1);
+
+        assert!(colors_match(reduced[0], Color::BLACK, 0.02));
+    }
+
+    #[test]
+    fn only_used_colors_are_counted() {
+        let mut library = ColorLibrary::empty();
+        library.add_color(Color::RED);
+        library.add_color(Color::GREEN);
+        library.add_color(Color::RED);
+
+        let mut chunk = Chunk::flat_ground(1, 0, 2);
+        chunk.set_block(Coordinate::new(0, 0, 0), Some(Block::new(2)));
+
+        let used_colors = count_used_colors(&library, [&chunk].into_iter());
+
+        assert_eq!(
+            used_colors,
+            vec![
+                UsedColor {
+                    palette_index: 0,
+                    color: Color::RED,
+                    block_count: 3
+                },
+                UsedColor {
+                    palette_index: 2,
+                    color: Color::RED,
+                    block_count: 1
+                },
+            ]
+        );
+        assert_eq!(
+            map_palette_indices(&library, &used_colors, &[0, 0]),
+            vec![Some(0), None, Some(0)]
+        );
+    }
+
+    #[test]
+    fn entries_that_share_a_color_keep_their_own_material() {
+        let mut metal = PaletteColor::with_name(Color::RED, "metal");
+        metal.material.metallic = 1.0;
+
+        let library = ColorLibrary::from_colors(
+            "test",
+            vec![PaletteColor::with_name(Color::RED, "brick"), metal.clone()],
+        );
+
+        let mut chunk = Chunk::flat_ground(1, 0, 2);
+        chunk.set_block(Coordinate::new(0, 0, 0), Some(Block::new(1)));
+
+        let used_colors = count_used_colors(&library, [&chunk].into_iter());
+        let palette_colors =
+            reduced_palette_colors(&library, &used_colors, &[Color::RED, Color::RED], &[0, 1]);
+
+        assert_eq!(used_colors.len(), 2);
+        assert_eq!(palette_colors[0].name, "brick");
+        assert_eq!(palette_colors[1].name, "metal");
+        assert_eq!(palette_colors[1].material, metal.material);
+    }
+
+    #[test]
+    fn reduced_entries_keep_name_and_material_of_the_most_used_color() {
+        let mut glass = PaletteColor::with_name(Color::rgba(0.0, 0.0, 1.0, 0.5), "glass");
+        glass.material.metallic = 0.8;
+
+        let library = ColorLibrary::from_colors(
+            "test",
+            vec![
+                PaletteColor::with_name(Color::rgba(0.02, 0.0, 0.97, 0.5), "rare"),
+                glass.clone(),
+            ],
+        );
+        let used_colors = vec![
+            UsedColor {
+                palette_index: 0,
+                color: library.all_colors()[0],
+                block_count: 1,
+            },
+            UsedColor {
+                palette_index: 1,
+                color: library.all_colors()[1],
+                block_count: 9,
+            },
+        ];
+
+        let palette_colors =
+            reduced_palette_colors(&library, &used_colors, &[Color::BLUE], &[0, 0]);
+
+        assert_eq!(palette_colors.len(), 1);
+        assert_eq!(palette_colors[0].name, "glass");
+        assert_eq!(palette_colors[0].material, glass.material);
+        assert_eq!(palette_colors[0].color, Color::BLUE.with_a(0.5));
+    }
+
+    #[test]
+    fn blocks_outside_the_old_palette_get_the_first_color() {
+        let mut chunk = Chunk::flat_ground(1, 1, 2);
+        chunk.set_block(Coordinate::new(0, 0, 0), Some(Block::new(5)));
+
+        remap_to_reduced_palette(&mut chunk, &[None, Some(1)]);
+
+        assert_eq!(
+            chunk.get_block(Coordinate::new(0, 0, 0)),
+            Some(Block::new(0))
+        );
+        assert_eq!(
+            chunk.get_block(Coordinate::new(1, 0, 0)),
+            Some(Block::new(1))
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use bevy::prelude::*;
+
+use crate::player::editor_modes::{build::OnPlaceBlockRequest, paint::OnPaintBlockRequest};
+
+use super::{ColorLibrary, OnColorClicked};
+
+pub struct RecentColorsPlugin;
+
+impl Plugin for RecentColorsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RecentColors::new(MAX_RECENT_COLORS))
+            .add_systems(Startup, spawn_recent_colors_container)
+            .add_systems(
+                Update,
+                (
+                    track_placed_colors,
+                    recent_button_interaction,
+                    rebuild_recent_buttons.run_if(
+                        resource_changed::<RecentColors>()
+                            .or_else(resource_changed::<ColorLibrary>()),
+                    ),
+                ),
+            );
+    }
+}
+
+const MAX_RECENT_COLORS: usize = 8;
+
+/// The palette entries that were used last by index, most recent first.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct RecentColors {
+    indices: Vec<usize>,
+    max_count: usize,
+}
+
+impl RecentColors {
+    pub fn new(max_count: usize) -> Self {
+        Self {
+            indices: Vec::new(),
+            max_count,
+        }
+    }
+
+    /// Moves the entry to the front, dropping the oldest entry when the list is full.
+    pub fn push(&mut self, index: usize) {
+        self.indices.retain(|recent| *recent != index);
+        self.indices.insert(0, index);
+        self.indices.truncate(self.max_count);
+    }
+
+    pub fn most_recent(&self) -> Option<usize> {
+        self.indices.first().copied()
+    }
+
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Follows palette entries that moved, entries mapped to `None` are dropped.
+    pub fn remap_indices(&mut self, remap: impl Fn(usize) -> Option<usize>) {
+        self.indices = self
+            .indices
+            .iter()
+            .filter_map(|index| remap(*index))
+            .collect();
+    }
+}
+
+#[derive(Component)]
+struct RecentColorsContainer;
+
+#[derive(Component, Clone, Copy)]
+struct RecentColorButton {
+    index: usize,
+}
+
+fn track_placed_colors(
+    mut place_events: EventReader<OnPlaceBlockRequest>,
+    mut paint_events: EventReader<OnPaintBlockRequest>,
+    color_library: Res<ColorLibrary>,
+    mut recent_colors: ResMut<RecentColors>,
+) {
+    let placed_indices = place_events
+        .iter()
+        .filter_map(|event| event.block)
+        .map(|block| block.palette_index());
+    let painted_indices = paint_events.iter().map(|event| event.palette_index);
+
+    for index in placed_indices
+        .chain(painted_indices)
+        .filter(|index| *index < color_library.count())
+    {
+        // Only touches the resource when something changes, so the buttons aren't rebuilt every frame.
+        if recent_colors.most_recent() != Some(index) {
+            recent_colors.push(index);
+        }
+    }
+}
+
+fn recent_button_interaction(
+    buttons: Query<(&RecentColorButton, &Interaction), Changed<Interaction>>,
+    mut on_clicked: EventWriter<OnColorClicked>,
+) {
+    for (button, interaction) in buttons.iter() {
+        if *interaction == Interaction::Pressed {
+            on_clicked.send(OnColorClicked::new(button.index));
+        }
+    }
+}
+
+// UI
+
+fn spawn_recent_colors_container(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Recent colors container"),
+        RecentColorsContainer,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                bottom: Val::Px(60.0),
+                padding: UiRect::horizontal(Val::Px(10.0)),
+                column_gap: Val::Px(6.0),
+                ..default()
+            },
+            ..default()
+        },
+    ));
+}
+
+fn rebuild_recent_buttons(
+    mut commands: Commands,
+    recent_colors: Res<RecentColors>,
+    color_library: Res<ColorLibrary>,
+    containers: Query<Entity, With<RecentColorsContainer>>,
+) {
+    for container in containers.iter() {
+        commands
+            .entity(container)
+            .despawn_descendants()
+            .with_children(|list| {
+                // A loaded palette can be shorter than the one the recent entries came from.
+                for (index, color) in recent_colors
+                    .indices()
+                    .iter()
+                    .filter_map(|index| Some((*index, color_library.color_at(*index)?)))
+                {
+                    list.spawn((
+                        Name::new("Recent color button"),
+                        RecentColorButton { index },
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(24.0),
+                                height: Val::Px(24.0),
+                                ..default()
+                            },
+                            background_color: color.into(),
+                            ..default()
+                        },
+                    ));
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn most_recent_color_comes_first() {
+        let mut recent_colors = RecentColors::new(4);
+
+        recent_colors.push(3);
+        recent_colors.push(1);
+
+        assert_eq!(recent_colors.indices(), &[1, 3]);
+        assert_eq!(recent_colors.most_recent(), Some(1));
+    }
+
+    #[test]
+    fn reused_color_moves_to_front_without_duplicates() {
+        let mut recent_colors = RecentColors::new(4);
+
+        recent_colors.push(3);
+        recent_colors.push(1);
+        recent_colors.push(3);
+
+        assert_eq!(recent_colors.indices(), &[3, 1]);
+    }
+
+    #[test]
+    fn oldest_color_is_dropped_when_full() {
+        let mut recent_colors = RecentColors::new(2);
+
+        recent_colors.push(0);
+        recent_colors.push(1);
+        recent_colors.push(2);
+
+        assert_eq!(recent_colors.indices(), &[2, 1]);
+    }
+
+    #[test]
+    fn recent_entries_follow_palette_changes() {
+        let mut recent_colors = RecentColors::new(4);
+
+        recent_colors.push(0);
+        recent_colors.push(2);
+        recent_colors.push(1);
+        recent_colors.remap_indices(|index| (index != 1).then(|| index.min(1)));
+
+        assert_eq!(recent_colors.indices(), &[1, 0]);
+    }
+}
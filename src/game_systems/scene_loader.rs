@@ -2,8 +2,14 @@ use std::fs;
 
 use bevy::prelude::*;
 use ron::Error;
+use serde::{Deserialize, Serialize};
 
-use crate::world::chunk::Chunk;
+use crate::{
+    player::editor_actions::{EditorAction, OnEditorAction},
+    world::{block::Block, chunk::Chunk},
+};
+
+use super::color_library::{palette_storage::sanitize_file_name, ColorLibrary};
 
 pub struct SceneLoaderPlugin;
 
@@ -14,7 +20,7 @@ impl Plugin for SceneLoaderPlugin {
             .add_systems(
                 Update,
                 (
-                    send_requests_on_keyboard_input,
+                    send_requests_on_actions,
                     handle_save_requests,
                     handle_load_requests,
                 ),
@@ -22,37 +28,122 @@ impl Plugin for SceneLoaderPlugin {
     }
 }
 
+const SCENES_DIRECTORY: &str = "assets/scenes";
 const FILE_PATH_TO_SAVES: &str = "assets/scenes/test_save.ron";
 
-const SAVE_KEY: KeyCode = KeyCode::I;
-const LOAD_KEY: KeyCode = KeyCode::O;
+/// Saves the scene, to `assets/scenes/<name>.ron` if it has a name.
+#[derive(Event, Default)]
+pub struct OnSaveSceneRequest {
+    pub name: Option<String>,
+}
+
+impl OnSaveSceneRequest {
+    pub fn new(name: Option<String>) -> Self {
+        Self { name }
+    }
+}
 
-#[derive(Event)]
-struct OnSaveSceneRequest;
+/// Loads the scene, from `assets/scenes/<name>.ron` if it has a name.
+#[derive(Event, Default)]
+pub struct OnLoadSceneRequest {
+    pub name: Option<String>,
+}
+
+impl OnLoadSceneRequest {
+    pub fn new(name: Option<String>) -> Self {
+        Self { name }
+    }
+}
+
+/// Everything that is stored in a scene file.
+#[derive(Serialize, Deserialize)]
+struct SceneData {
+    chunk: Chunk,
+    palette: ColorLibrary,
+}
+
+/// Older saves stored a color in every block instead of a palette index.
+#[derive(Deserialize)]
+struct LegacySceneData {
+    chunk: LegacyChunk,
+    palette: ColorLibrary,
+}
+
+#[derive(Deserialize)]
+struct LegacyChunk {
+    blocks: Vec<Option<LegacyBlock>>,
+    size: usize,
+}
+
+#[derive(Deserialize)]
+struct LegacyBlock {
+    color: Color,
+}
 
-#[derive(Event)]
-struct OnLoadSceneRequest;
+impl LegacyChunk {
+    /// Gives every block the palette index of its color, adding colors that aren't in the palette yet.
+    ///
+    /// Fails if the scene uses more colors than a palette can hold.
+    fn into_chunk(self, palette: &mut ColorLibrary) -> Result<Chunk, Error> {
+        let mut chunk = Chunk::empty(self.size);
 
-fn send_requests_on_keyboard_input(
-    input: Res<Input<KeyCode>>,
+        for (index, block) in self.blocks.iter().enumerate() {
+            if let Some(block) = block {
+                let palette_index = palette.index_of_or_add(block.color).ok_or_else(|| {
+                    Error::Message(format!(
+                        "the scene uses more than {} colors",
+                        ColorLibrary::MAX_COLORS
+                    ))
+                })?;
+
+                chunk.set_block(
+                    chunk.index_to_coordinate(index),
+                    Some(Block::new(palette_index)),
+                );
+            }
+        }
+
+        chunk.set_changed();
+        Ok(chunk)
+    }
+}
+
+fn send_requests_on_actions(
+    mut on_action: EventReader<OnEditorAction>,
     mut on_save_request: EventWriter<OnSaveSceneRequest>,
     mut on_load_request: EventWriter<OnLoadSceneRequest>,
 ) {
-    if input.just_pressed(SAVE_KEY) {
-        println!("Request save");
-        on_save_request.send(OnSaveSceneRequest);
-    }
-
-    if input.just_pressed(LOAD_KEY) {
-        println!("Request load");
-        on_load_request.send(OnLoadSceneRequest);
+    for event in on_action.iter() {
+        match event.action {
+            EditorAction::SaveScene => {
+                println!("Request save");
+                on_save_request.send(OnSaveSceneRequest::default());
+            }
+            EditorAction::LoadScene => {
+                println!("Request load");
+                on_load_request.send(OnLoadSceneRequest::default());
+            }
+            _ => {}
+        }
     }
 }
 
-fn handle_save_requests(chunks: Query<&Chunk>, on_save_request: EventReader<OnSaveSceneRequest>) {
-    if !on_save_request.is_empty() {
-        if let Err(error) = save_chunk_to_file(chunks.single(), FILE_PATH_TO_SAVES) {
-            println!("Error while saving chunk: {:?}", error);
+fn handle_save_requests(
+    chunks: Query<&Chunk>,
+    color_library: Res<ColorLibrary>,
+    mut on_save_request: EventReader<OnSaveSceneRequest>,
+) {
+    for request in on_save_request.iter() {
+        let scene = SceneData {
+            chunk: chunks.single().clone(),
+            palette: color_library.clone(),
+        };
+
+        let path = scene_path(request.name.as_deref());
+
+        match save_scene_to_file(&scene, &path) {
+            Ok(()) => println!("Saved scene to {}", path),
+            Err(error) => println!("Error while saving scene: {:?}", error),
         }
     }
 }
@@ -60,29 +151,155 @@ fn handle_save_requests(chunks: Query<&Chunk>, on_save_request: EventReader<OnSa
 fn handle_load_requests(
     mut commands: Commands,
     chunks: Query<Entity, With<Chunk>>,
-    on_load_request: EventReader<OnLoadSceneRequest>,
+    mut color_library: ResMut<ColorLibrary>,
+    mut on_load_request: EventReader<OnLoadSceneRequest>,
 ) {
-    if !on_load_request.is_empty() {
+    if let Some(request) = on_load_request.iter().last() {
         let chunk_entity = chunks.single();
 
-        match load_chunk_from_file(FILE_PATH_TO_SAVES) {
-            Ok(mut new_chunk) => {
+        match load_scene_from_file(&scene_path(request.name.as_deref()), &color_library) {
+            Ok((mut new_chunk, palette)) => {
                 new_chunk.set_changed();
                 commands.entity(chunk_entity).insert(new_chunk);
+
+                *color_library = palette;
             }
             Err(error) => {
-                println!("Error while loading chunk: {:?}", error);
+                println!("Error while loading scene: {:?}", error);
             }
         }
     }
 }
 
-fn save_chunk_to_file(chunk: &Chunk, path: &str) -> Result<(), Error> {
-    let serialized = ron::to_string(&chunk)?;
+fn scene_path(name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!("{}/{}.ron", SCENES_DIRECTORY, sanitize_file_name(name)),
+        None => FILE_PATH_TO_SAVES.to_string(),
+    }
+}
+
+fn save_scene_to_file(scene: &SceneData, path: &str) -> Result<(), Error> {
+    let serialized = ron::to_string(scene)?;
     Ok(fs::write(path, serialized)?)
 }
 
-fn load_chunk_from_file(path: &str) -> Result<Chunk, Error> {
+fn load_scene_from_file(
+    path: &str,
+    current_palette: &ColorLibrary,
+) -> Result<(Chunk, ColorLibrary), Error> {
     let file = fs::read_to_string(path)?;
-    Ok(ron::from_str::<Chunk>(&file)?)
+    parse_scene(&file, current_palette)
+}
+
+/// Parses the chunk and its palette. Older saves are converted, their block colors are added to
+/// the saved palette, or to the current palette if the save has none.
+fn parse_scene(file: &str, current_palette: &ColorLibrary) -> Result<(Chunk, ColorLibrary), Error> {
+    if let Ok(scene) = ron::from_str::<SceneData>(file) {
+        return Ok((scene.chunk, scene.palette));
+    }
+
+    let (legacy_chunk, palette) = match ron::from_str::<LegacySceneData>(file) {
+        Ok(scene) => (scene.chunk, Some(scene.palette)),
+        Err(_) => (ron::from_str::<LegacyChunk>(file)?, None),
+    };
+
+    let mut palette = palette.unwrap_or_else(|| current_palette.clone());
+    let chunk = legacy_chunk.into_chunk(&mut palette)?;
+
+    Ok((chunk, palette))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::newtypes::coordinate::Coordinate;
+
+    use super::*;
+
+    const LEGACY_CHUNK: &str =
+        "(blocks:[Some((color:Rgba(red:1.0,green:0.0,blue:0.0,alpha:1.0))),None],data_changed:false,size:1)";
+
+    #[test]
+    fn named_scenes_get_their_own_file() {
+        assert_eq!(scene_path(None), FILE_PATH_TO_SAVES);
+        assert_eq!(scene_path(Some("my castle")), "assets/scenes/my_castle.ron");
+    }
+
+    #[test]
+    fn scene_is_saved_with_palette() {
+        let mut palette = ColorLibrary::with_default_colors();
+        palette.set_name("scene palette");
+
+        let scene = SceneData {
+            chunk: Chunk::flat_ground(1, 2, 4),
+            palette: palette.clone(),
+        };
+
+        let (chunk, loaded_palette) =
+            parse_scene(&ron::to_string(&scene).unwrap(), &ColorLibrary::empty()).unwrap();
+
+        assert_eq!(loaded_palette, palette);
+        assert_eq!(
+            chunk.get_block(Coordinate::new(0, 0, 0)),
+            Some(Block::new(2))
+        );
+    }
+
+    #[test]
+    fn legacy_colors_become_palette_indices() {
+        let mut palette = ColorLibrary::empty();
+        palette.add_color(Color::RED);
+
+        let legacy_chunk = LegacyChunk {
+            blocks: [Some(Color::BLUE), None, Some(Color::RED), Some(Color::BLUE)]
+                .into_iter()
+                .map(|color| color.map(|color| LegacyBlock { color }))
+                .collect(),
+            size: 2,
+        };
+        let chunk = legacy_chunk.into_chunk(&mut palette).unwrap();
+
+        assert_eq!(palette.all_colors(), vec![Color::RED, Color::BLUE]);
+        assert_eq!(
+            chunk.get_block(Coordinate::new(0, 0, 0)),
+            Some(Block::new(1))
+        );
+        assert_eq!(chunk.get_block(Coordinate::new(1, 0, 0)), None);
+        assert_eq!(
+            chunk.get_block(Coordinate::new(0, 1, 0)),
+            Some(Block::new(0))
+        );
+        assert_eq!(chunk.count_blocks_with_palette_index(1), 2);
+    }
+
+    #[test]
+    fn can_load_legacy_chunk_without_palette() {
+        let current_palette = ColorLibrary::with_default_colors();
+
+        let (chunk, palette) = parse_scene(LEGACY_CHUNK, &current_palette).unwrap();
+
+        let red_index = current_palette.count();
+
+        assert_eq!(palette.color_at(red_index), Some(Color::RED));
+        assert_eq!(
+            chunk.get_block(Coordinate::new(0, 0, 0)),
+            Some(Block::new(red_index))
+        );
+    }
+
+    #[test]
+    fn can_load_legacy_scene_with_palette() {
+        let file = format!(
+            "(chunk:{},palette:{})",
+            LEGACY_CHUNK,
+            ron::to_string(&ColorLibrary::empty()).unwrap()
+        );
+
+        let (chunk, palette) = parse_scene(&file, &ColorLibrary::with_default_colors()).unwrap();
+
+        assert_eq!(palette.all_colors(), vec![Color::RED]);
+        assert_eq!(
+            chunk.get_block(Coordinate::new(0, 0, 0)),
+            Some(Block::new(0))
+        );
+    }
 }
@@ -0,0 +1,234 @@
+use bevy::{input::InputSystem, prelude::*};
+
+pub struct TextFieldPlugin;
+
+impl Plugin for TextFieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OnTextFieldSubmitted>()
+            .add_systems(PreUpdate, capture_text_input.after(InputSystem))
+            .add_systems(Update, (focus_clicked_field, update_field_text).chain());
+    }
+}
+
+const DELETE_KEY: KeyCode = KeyCode::Back;
+const SUBMIT_KEY: KeyCode = KeyCode::Return;
+const CANCEL_KEY: KeyCode = KeyCode::Escape;
+
+const FIELD_COLOR: Color = Color::DARK_GRAY;
+const FOCUSED_FIELD_COLOR: Color = Color::GRAY;
+
+/// A clickable text input, while it's focused all keyboard input goes to this field.
+#[derive(Component, Clone, Debug)]
+pub struct TextField {
+    pub text: String,
+    is_focused: bool,
+    max_length: usize,
+    accepts: fn(char) -> bool,
+}
+
+impl TextField {
+    pub fn new(text: impl Into<String>, max_length: usize, accepts: fn(char) -> bool) -> Self {
+        Self {
+            text: text.into(),
+            is_focused: false,
+            max_length,
+            accepts,
+        }
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    pub fn set_focused(&mut self, is_focused: bool) {
+        self.is_focused = is_focused;
+    }
+
+    fn type_character(&mut self, character: char) {
+        if !character.is_control()
+            && (self.accepts)(character)
+            && self.text.chars().count() < self.max_length
+        {
+            self.text.push(character);
+        }
+    }
+
+    fn display_text(&self) -> String {
+        if self.is_focused {
+            format!("{}_", self.text)
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
+/// Sent when enter is pressed in a focused text field.
+#[derive(Event, Debug, Clone)]
+pub struct OnTextFieldSubmitted {
+    pub field: Entity,
+    pub text: String,
+}
+
+#[derive(Component)]
+struct TextFieldText;
+
+pub fn accepts_any(_character: char) -> bool {
+    true
+}
+
+pub fn text_field_style() -> TextStyle {
+    TextStyle {
+        font_size: 16.0,
+        color: Color::WHITE,
+        ..default()
+    }
+}
+
+/// Spawns a text field as child, with the extra components given.
+pub fn spawn_text_field(
+    parent: &mut ChildBuilder,
+    text_field: TextField,
+    width: Val,
+    components: impl Bundle,
+) -> Entity {
+    let display_text = text_field.display_text();
+
+    parent
+        .spawn((
+            Name::new("Text field"),
+            text_field,
+            components,
+            ButtonBundle {
+                style: Style {
+                    width,
+                    padding: UiRect::all(Val::Px(4.0)),
+                    ..default()
+                },
+                background_color: FIELD_COLOR.into(),
+                ..default()
+            },
+        ))
+        .with_children(|field| {
+            field.spawn((
+                TextFieldText,
+                TextBundle::from_section(display_text, text_field_style()),
+            ));
+        })
+        .id()
+}
+
+fn focus_clicked_field(
+    mut fields: Query<(Entity, &mut TextField, &Interaction)>,
+    mouse_input: Res<Input<MouseButton>>,
+) {
+    if mouse_input.get_just_pressed().next().is_none() {
+        return;
+    }
+
+    let clicked_field = fields
+        .iter()
+        .find(|(_, _, interaction)| **interaction == Interaction::Pressed)
+        .map(|(entity, _, _)| entity);
+
+    for (entity, mut text_field, _) in fields.iter_mut() {
+        let should_focus = Some(entity) == clicked_field;
+
+        if text_field.is_focused != should_focus {
+            text_field.is_focused = should_focus;
+        }
+    }
+}
+
+/// Types into the focused text field, the keyboard input is consumed so it doesn't trigger other shortcuts.
+/// Escape leaves the field without submitting.
+/// Runs before editor actions are read, so typing in a field doesn't trigger shortcuts.
+pub fn capture_text_input(
+    mut fields: Query<(Entity, &mut TextField)>,
+    mut received_characters: EventReader<ReceivedCharacter>,
+    mut key_input: ResMut<Input<KeyCode>>,
+    mut on_submitted: EventWriter<OnTextFieldSubmitted>,
+) {
+    let Some((entity, mut text_field)) = fields
+        .iter_mut()
+        .find(|(_, text_field)| text_field.is_focused)
+    else {
+        received_characters.clear();
+        return;
+    };
+
+    for event in received_characters.iter() {
+        text_field.type_character(event.char);
+    }
+
+    if key_input.just_pressed(DELETE_KEY) {
+        text_field.text.pop();
+    }
+
+    if key_input.just_pressed(CANCEL_KEY) {
+        text_field.is_focused = false;
+    } else if key_input.just_pressed(SUBMIT_KEY) {
+        text_field.is_focused = false;
+
+        on_submitted.send(OnTextFieldSubmitted {
+            field: entity,
+            text: text_field.text.clone(),
+        });
+    }
+
+    key_input.reset_all();
+}
+
+fn update_field_text(
+    mut fields: Query<(&TextField, &Children, &mut BackgroundColor), Changed<TextField>>,
+    mut texts: Query<&mut Text, With<TextFieldText>>,
+) {
+    for (text_field, children, mut background) in fields.iter_mut() {
+        background.0 = if text_field.is_focused {
+            FOCUSED_FIELD_COLOR
+        } else {
+            FIELD_COLOR
+        };
+
+        let mut texts = texts.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.sections[0].value = text_field.display_text();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typing_respects_max_length_and_filter() {
+        let mut text_field = TextField::new("", 3, |character| character.is_ascii_digit());
+
+        for character in "1a2b34".chars() {
+            text_field.type_character(character);
+        }
+
+        assert_eq!(text_field.text, "123");
+    }
+
+    #[test]
+    fn control_characters_are_not_typed() {
+        let mut text_field = TextField::new("ab", 10, accepts_any);
+
+        text_field.type_character('\u{8}');
+        text_field.type_character('\r');
+
+        assert_eq!(text_field.text, "ab");
+    }
+
+    #[test]
+    fn focused_field_shows_cursor() {
+        let mut text_field = TextField::new("name", 10, accepts_any);
+
+        assert_eq!(text_field.display_text(), "name");
+
+        text_field.is_focused = true;
+
+        assert_eq!(text_field.display_text(), "name_");
+    }
+}
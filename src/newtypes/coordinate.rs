@@ -1,4 +1,4 @@
-use bevy::prelude::Vec3;
+use bevy::prelude::{IVec3, Vec3};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Coordinate {
@@ -11,6 +11,15 @@ impl Coordinate {
     pub fn new(x: usize, y: usize, z: usize) -> Self {
         Self { x, y, z }
     }
+
+    /// Returns this coordinate moved by the given offset, or None if it would become negative.
+    pub fn offset(&self, offset: IVec3) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_add_signed(offset.x as isize)?,
+            y: self.y.checked_add_signed(offset.y as isize)?,
+            z: self.z.checked_add_signed(offset.z as isize)?,
+        })
+    }
 }
 
 impl From<Vec3> for Coordinate {
@@ -1,4 +1,4 @@
-use bevy::prelude::Vec3;
+use bevy::prelude::{IVec3, Vec3};
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Direction {
@@ -40,6 +40,11 @@ impl Direction {
         Vec3::new(self.x, self.y, self.z)
     }
 
+    /// Rounds this direction to the nearest whole step on each axis, useful for moving between coordinates.
+    pub fn to_offset(&self) -> IVec3 {
+        self.to_vector().round().as_ivec3()
+    }
+
     pub fn from_vector(vector: Vec3) -> Self {
         Self::validate(vector);
         Self {
@@ -49,6 +54,29 @@ impl Direction {
         }
     }
 
+    /// Returns the axis direction closest to the vector, like the side of a block a slanted normal belongs to.
+    pub fn nearest_axis(vector: Vec3) -> Self {
+        let abs = vector.abs();
+
+        if abs.y >= abs.x && abs.y >= abs.z {
+            if vector.y >= 0.0 {
+                Self::Y
+            } else {
+                Self::NEG_Y
+            }
+        } else if abs.x >= abs.z {
+            if vector.x >= 0.0 {
+                Self::X
+            } else {
+                Self::NEG_X
+            }
+        } else if vector.z >= 0.0 {
+            Self::Z
+        } else {
+            Self::NEG_Z
+        }
+    }
+
     fn validate(value: Vec3) {
         if value.length() != 1.0 {
             panic!(
@@ -97,4 +125,26 @@ mod tests {
 
         assert_eq!(result, Vec3::Y);
     }
+
+    #[test]
+    fn slanted_vectors_snap_to_nearest_axis() {
+        assert_eq!(
+            Direction::nearest_axis(Vec3::new(0.0, 0.7, 0.7)),
+            Direction::Y
+        );
+        assert_eq!(
+            Direction::nearest_axis(Vec3::new(-0.8, 0.1, 0.6)),
+            Direction::NEG_X
+        );
+        assert_eq!(
+            Direction::nearest_axis(Vec3::new(0.1, -0.2, -0.9)),
+            Direction::NEG_Z
+        );
+    }
+
+    #[test]
+    fn can_get_offset_from_direction() {
+        assert_eq!(Direction::NEG_Z.to_offset(), IVec3::new(0, 0, -1));
+        assert_eq!(Direction::X.to_offset(), IVec3::X);
+    }
 }
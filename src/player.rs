@@ -1,12 +1,23 @@
 pub mod camera;
-mod mouse_interaction;
+mod command_console;
+mod config_file;
+pub mod editor_actions;
 pub mod editor_modes;
+pub mod editor_settings;
+mod gamepad;
+mod mouse_interaction;
+#[cfg(test)]
+mod test_input;
 
 use bevy::prelude::*;
 use camera::EditorCameraPlugin;
+use command_console::CommandConsolePlugin;
 use mouse_interaction::MouseInteractionPlugin;
 
-use self::editor_modes::EditorModesPlugin;
+use self::{
+    editor_actions::EditorActionsPlugin, editor_modes::EditorModesPlugin,
+    editor_settings::EditorSettingsPlugin, gamepad::EditorGamepadPlugin,
+};
 
 pub struct PlayerPlugin;
 
@@ -15,7 +26,11 @@ impl Plugin for PlayerPlugin {
         app.add_plugins((
             EditorCameraPlugin,
             MouseInteractionPlugin,
+            EditorActionsPlugin,
             EditorModesPlugin,
+            EditorGamepadPlugin,
+            EditorSettingsPlugin,
+            CommandConsolePlugin,
         ));
     }
 }
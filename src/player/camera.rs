@@ -1,14 +1,34 @@
-use bevy::prelude::*;
+use bevy::{input::InputSystem, prelude::*};
 use flying_camera::{FlyingCameraBundle, FlyingCameraPlugin};
 
-use crate::player::mouse_interaction::MouseInteraction;
+use crate::player::{
+    editor_actions::{send_editor_actions, Modifier},
+    mouse_interaction::{MouseInteraction, TargetingMode},
+};
+
+/// Keys the flying camera moves with, they are hidden from the camera while Ctrl or Alt is held,
+/// so editor shortcuts on these keys need Ctrl or Alt.
+pub const CAMERA_MOVEMENT_KEYS: [KeyCode; 5] = [
+    KeyCode::W,
+    KeyCode::A,
+    KeyCode::S,
+    KeyCode::D,
+    KeyCode::Space,
+];
 
 pub struct EditorCameraPlugin;
 
 impl Plugin for EditorCameraPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(FlyingCameraPlugin)
-            .add_systems(Startup, spawn_camera);
+            .add_systems(Startup, spawn_camera)
+            .add_systems(Update, hand_camera_control_to_gamepad)
+            .add_systems(
+                PreUpdate,
+                hide_camera_keys_in_chords
+                    .after(InputSystem)
+                    .after(send_editor_actions),
+            );
     }
 }
 
@@ -26,3 +46,76 @@ fn spawn_camera(mut commands: Commands, mut mouse_interaction: ResMut<MouseInter
 
     mouse_interaction.set_active_camera(camera_entity);
 }
+
+/// Takes the flying camera controls off the camera while the gamepad sticks fly it,
+/// so the two don't both write the camera transform in the same frame.
+fn hand_camera_control_to_gamepad(
+    mut commands: Commands,
+    mouse_interaction: Res<MouseInteraction>,
+    mut previous_mode: Local<TargetingMode>,
+) {
+    let targeting_mode = mouse_interaction.targeting_mode();
+
+    if targeting_mode == *previous_mode {
+        return;
+    }
+
+    *previous_mode = targeting_mode;
+
+    let Some(camera_entity) = mouse_interaction.active_camera() else {
+        return;
+    };
+
+    match targeting_mode {
+        TargetingMode::Crosshair => {
+            commands
+                .entity(camera_entity)
+                .remove::<FlyingCameraBundle>();
+        }
+        TargetingMode::Cursor => {
+            commands
+                .entity(camera_entity)
+                .insert(FlyingCameraBundle::default());
+        }
+    }
+}
+
+/// Runs after the editor actions are read, so Ctrl+S saves without also moving the camera backward.
+fn hide_camera_keys_in_chords(mut keys: ResMut<Input<KeyCode>>) {
+    let in_chord = Modifier::held(&keys)
+        .iter()
+        .any(|modifier| matches!(modifier, Modifier::Control | Modifier::Alt));
+
+    if in_chord {
+        for key in CAMERA_MOVEMENT_KEYS {
+            keys.reset(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::player::test_input::{input_app, press_key};
+
+    use super::*;
+
+    #[test]
+    fn camera_keys_are_hidden_while_ctrl_is_held() {
+        let mut app = input_app();
+        app.add_systems(PreUpdate, hide_camera_keys_in_chords.after(InputSystem));
+
+        press_key(&mut app, KeyCode::W);
+        app.update();
+
+        assert!(app.world.resource::<Input<KeyCode>>().pressed(KeyCode::W));
+
+        press_key(&mut app, KeyCode::ControlLeft);
+        press_key(&mut app, KeyCode::S);
+        app.update();
+
+        let keys = app.world.resource::<Input<KeyCode>>();
+        assert!(!keys.pressed(KeyCode::S));
+        assert!(!keys.pressed(KeyCode::W));
+        assert!(keys.pressed(KeyCode::ControlLeft));
+    }
+}
@@ -0,0 +1,374 @@
+mod commands;
+
+use bevy::{input::InputSystem, prelude::*};
+
+use crate::{
+    game_systems::{
+        color_library::ColorLibrary,
+        scene_loader::{OnLoadSceneRequest, OnSaveSceneRequest},
+        text_field::{
+            accepts_any, capture_text_input, spawn_text_field, text_field_style,
+            OnTextFieldSubmitted, TextField,
+        },
+    },
+    newtypes::coordinate::Coordinate,
+    world::{block::Block, WorldSettings},
+};
+
+use self::commands::{find_palette_index, palette_index_or_add, parse_command, ConsoleCommand};
+use super::{
+    editor_actions::{action_triggered, EditorAction, InputButton, InputMap, OnEditorAction},
+    editor_modes::{
+        build::OnPlaceBlockRequest,
+        replace_color::{get_replace_scope, OnReplaceColorRequest},
+        select::{get_coordinates_between, CurrentSelection, OnSelectAreaRequest},
+        EditorMode,
+    },
+};
+
+/*
+    Commands send the same requests as the mouse tools,
+    so they go through the same code that changes the world.
+*/
+
+pub struct CommandConsolePlugin;
+
+impl Plugin for CommandConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CommandConsole>()
+            .add_systems(Startup, spawn_command_console)
+            .add_systems(
+                PreUpdate,
+                leave_field_on_console_key
+                    .after(InputSystem)
+                    .before(capture_text_input),
+            )
+            .add_systems(
+                Update,
+                (toggle_command_console, run_submitted_commands).chain(),
+            );
+    }
+}
+
+const MAX_COMMAND_LENGTH: usize = 80;
+const PANEL_COLOR: Color = Color::rgba(0.1, 0.1, 0.1, 0.9);
+
+#[derive(Resource, Default)]
+struct CommandConsole {
+    is_open: bool,
+}
+
+#[derive(Component)]
+struct CommandConsolePanel;
+
+#[derive(Component)]
+struct CommandField;
+
+/// Shows the result of the last command.
+#[derive(Component)]
+struct CommandOutput;
+
+/// Opens the console with its field focused, so a command can be typed right away.
+fn toggle_command_console(
+    mut on_action: EventReader<OnEditorAction>,
+    mut command_console: ResMut<CommandConsole>,
+    mut panels: Query<&mut Style, With<CommandConsolePanel>>,
+    mut fields: Query<&mut TextField, With<CommandField>>,
+) {
+    if !action_triggered(&mut on_action, EditorAction::ToggleConsole) {
+        return;
+    }
+
+    command_console.is_open = !command_console.is_open;
+
+    for mut style in panels.iter_mut() {
+        style.display = if command_console.is_open {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+
+    for mut field in fields.iter_mut() {
+        field.set_focused(command_console.is_open);
+    }
+}
+
+/// The focused field takes all keys, so it's left before the console key is typed into it,
+/// this way the key still reaches the editor actions and closes the console.
+fn leave_field_on_console_key(
+    key_input: Res<Input<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut fields: Query<&mut TextField, With<CommandField>>,
+) {
+    let console_key_pressed = input_map.bindings().iter().any(|binding| {
+        binding.action == EditorAction::ToggleConsole
+            && matches!(binding.input, InputButton::Key(key) if key_input.just_pressed(key))
+            && binding
+                .modifiers
+                .iter()
+                .all(|modifier| key_input.any_pressed(modifier.keys()))
+    });
+
+    if !console_key_pressed {
+        return;
+    }
+
+    for mut field in fields.iter_mut() {
+        if field.is_focused() {
+            field.set_focused(false);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_submitted_commands(
+    mut on_submitted: EventReader<OnTextFieldSubmitted>,
+    mut fields: Query<&mut TextField, With<CommandField>>,
+    mut outputs: Query<&mut Text, With<CommandOutput>>,
+    world_settings: Res<WorldSettings>,
+    editor_mode: Res<State<EditorMode>>,
+    current_selection: Res<CurrentSelection>,
+    mut color_library: ResMut<ColorLibrary>,
+    mut next_editor_mode: ResMut<NextState<EditorMode>>,
+    mut on_place_request: EventWriter<OnPlaceBlockRequest>,
+    mut on_replace_request: EventWriter<OnReplaceColorRequest>,
+    mut on_select_request: EventWriter<OnSelectAreaRequest>,
+    mut on_save_request: EventWriter<OnSaveSceneRequest>,
+    mut on_load_request: EventWriter<OnLoadSceneRequest>,
+) {
+    for submitted in on_submitted.iter() {
+        let Ok(mut field) = fields.get_mut(submitted.field) else {
+            continue;
+        };
+
+        let result = parse_command(&submitted.text)
+            .map_err(|error| error.to_string())
+            .and_then(|command| match command {
+                ConsoleCommand::Fill { start, end, color } => {
+                    // Checked first, so a fill outside the chunk doesn't add its color to the palette.
+                    let coordinates = box_in_chunk(start, end, &world_settings)?;
+                    let block = fill_block(color.as_deref(), &mut color_library)?;
+                    let count = coordinates.len();
+
+                    on_place_request.send_batch(
+                        coordinates
+                            .into_iter()
+                            .map(|coord| OnPlaceBlockRequest { block, coord }),
+                    );
+
+                    Ok(format!("Filled {} blocks", count))
+                }
+                ConsoleCommand::Replace { from, to } => {
+                    let from_index = find_palette_index(&from, &color_library)
+                        .ok_or_else(|| format!("'{}' is not a color in the palette", from))?;
+                    let to_index = palette_index_or_add(&to, &mut color_library)
+                        .ok_or_else(|| format!("'{}' is not a color", to))?;
+
+                    let scope = get_replace_scope(*editor_mode.get(), &current_selection);
+                    on_replace_request
+                        .send(OnReplaceColorRequest::new(from_index, to_index, 0.0, scope));
+
+                    Ok(format!("Replaced {} with {}", from, to))
+                }
+                ConsoleCommand::Select { start, end } => {
+                    box_in_chunk(start, end, &world_settings)?;
+
+                    on_select_request.send(OnSelectAreaRequest::new(start, end));
+                    next_editor_mode.set(EditorMode::Select);
+
+                    Ok("Selected the area".to_string())
+                }
+                ConsoleCommand::Save(name) => {
+                    on_save_request.send(OnSaveSceneRequest::new(name));
+                    Ok("Saving scene".to_string())
+                }
+                ConsoleCommand::Load(name) => {
+                    on_load_request.send(OnLoadSceneRequest::new(name));
+                    Ok("Loading scene".to_string())
+                }
+                ConsoleCommand::Help => Ok(ConsoleCommand::USAGE.join(", ")),
+            });
+
+        let message = result.unwrap_or_else(|error| error);
+        println!("{}", message);
+
+        for mut text in outputs.iter_mut() {
+            text.sections[0].value = message.clone();
+        }
+
+        // Stays focused so the next command can be typed right away, escape leaves the field.
+        field.text.clear();
+        field.set_focused(true);
+    }
+}
+
+/// The block to fill with, the selected color is used when no color is given.
+fn fill_block(
+    color: Option<&str>,
+    color_library: &mut ColorLibrary,
+) -> Result<Option<Block>, String> {
+    let palette_index = match color {
+        Some(color) => palette_index_or_add(color, color_library)
+            .ok_or_else(|| format!("'{}' is not a color", color))?,
+        None => color_library
+            .selected_color()
+            .map(|_| color_library.selected_index())
+            .ok_or("Select a color or give one")?,
+    };
+
+    Ok(Some(Block::new(palette_index)))
+}
+
+/// Every coordinate in the box between start and end, if the box fits in the chunk.
+fn box_in_chunk(
+    start: Coordinate,
+    end: Coordinate,
+    world_settings: &WorldSettings,
+) -> Result<Vec<Coordinate>, String> {
+    let size = world_settings.chunk_size();
+    let fits = |coord: Coordinate| coord.x < size && coord.y < size && coord.z < size;
+
+    if fits(start) && fits(end) {
+        Ok(get_coordinates_between(start, end))
+    } else {
+        Err(format!("Coordinates go from 0 to {}", size - 1))
+    }
+}
+
+// UI
+
+fn spawn_command_console(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Command console"),
+            CommandConsolePanel,
+            Interaction::default(),
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(10.0),
+                    right: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(10.0)),
+                    row_gap: Val::Px(6.0),
+                    ..default()
+                },
+                background_color: PANEL_COLOR.into(),
+                ..default()
+            },
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                CommandOutput,
+                TextBundle::from_section("Type 'help' to list the commands", text_field_style()),
+            ));
+            spawn_text_field(
+                panel,
+                TextField::new("", MAX_COMMAND_LENGTH, accepts_any),
+                Val::Percent(100.0),
+                CommandField,
+            );
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::player::{
+        mouse_interaction::{update_mouse_on_ui, MouseInteraction},
+        test_input::{input_app, press_key},
+    };
+
+    use super::*;
+
+    #[test]
+    fn boxes_have_to_fit_in_the_chunk() {
+        let world_settings = WorldSettings::new(16, 1.0);
+
+        let coordinates = box_in_chunk(
+            Coordinate::new(0, 0, 0),
+            Coordinate::new(15, 1, 2),
+            &world_settings,
+        );
+
+        assert_eq!(coordinates.map(|coordinates| coordinates.len()), Ok(96));
+        assert!(box_in_chunk(
+            Coordinate::new(0, 0, 0),
+            Coordinate::new(16, 0, 0),
+            &world_settings
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn fill_uses_the_selected_color_when_none_is_given() {
+        let mut color_library = ColorLibrary::with_default_colors();
+        color_library.select_index(2);
+
+        assert_eq!(
+            fill_block(None, &mut color_library),
+            Ok(Some(Block::new(2)))
+        );
+        assert_eq!(
+            fill_block(Some("red"), &mut color_library),
+            Ok(Some(Block::new(3)))
+        );
+        assert!(fill_block(None, &mut ColorLibrary::empty()).is_err());
+    }
+
+    #[test]
+    fn clicking_the_console_panel_is_on_ui() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<MouseInteraction>()
+            .add_systems(Startup, spawn_command_console)
+            .add_systems(Update, update_mouse_on_ui);
+        app.update();
+
+        // Done by the ui focus system when the panel's padding is clicked.
+        let mut interactions = app
+            .world
+            .query_filtered::<&mut Interaction, With<CommandConsolePanel>>();
+        *interactions.single_mut(&mut app.world) = Interaction::Pressed;
+        app.update();
+
+        assert!(app.world.resource::<MouseInteraction>().mouse_on_ui());
+    }
+
+    #[test]
+    fn console_key_is_not_typed_into_the_console_field() {
+        let mut app = input_app();
+        app.add_event::<OnTextFieldSubmitted>()
+            .add_event::<ReceivedCharacter>()
+            .init_resource::<InputMap>()
+            .add_systems(
+                PreUpdate,
+                (
+                    leave_field_on_console_key.after(InputSystem),
+                    capture_text_input.after(InputSystem),
+                )
+                    .chain(),
+            );
+
+        let mut field = TextField::new("", MAX_COMMAND_LENGTH, accepts_any);
+        field.set_focused(true);
+        let field = app.world.spawn((field, CommandField)).id();
+
+        press_key(&mut app, KeyCode::Grave);
+        app.world.send_event(ReceivedCharacter {
+            window: Entity::PLACEHOLDER,
+            char: '`',
+        });
+        app.update();
+
+        let field = app.world.get::<TextField>(field).unwrap();
+        assert!(!field.is_focused());
+        assert!(field.text.is_empty());
+        assert!(app
+            .world
+            .resource::<Input<KeyCode>>()
+            .just_pressed(KeyCode::Grave));
+    }
+}
@@ -0,0 +1,259 @@
+use std::fmt;
+
+use bevy::prelude::*;
+
+use crate::{
+    game_systems::color_library::{color_picker::color_from_hex, ColorLibrary},
+    newtypes::coordinate::Coordinate,
+};
+
+/// Colors that can be written by name, besides the names of palette colors.
+const NAMED_COLORS: [(&str, Color); 12] = [
+    ("red", Color::RED),
+    ("green", Color::GREEN),
+    ("blue", Color::BLUE),
+    ("yellow", Color::YELLOW),
+    ("cyan", Color::CYAN),
+    ("magenta", Color::FUCHSIA),
+    ("orange", Color::ORANGE),
+    ("purple", Color::PURPLE),
+    ("white", Color::WHITE),
+    ("gray", Color::GRAY),
+    ("black", Color::BLACK),
+    ("lime", Color::LIME_GREEN),
+];
+
+/// A command typed in the console, colors are kept as text until they are looked up in the palette.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConsoleCommand {
+    /// Places blocks in the box between two coordinates, with the selected color if none is given.
+    Fill {
+        start: Coordinate,
+        end: Coordinate,
+        color: Option<String>,
+    },
+    /// Gives blocks of one color another color, inside the selection if there is one.
+    Replace {
+        from: String,
+        to: String,
+    },
+    Select {
+        start: Coordinate,
+        end: Coordinate,
+    },
+    Save(Option<String>),
+    Load(Option<String>),
+    Help,
+}
+
+impl ConsoleCommand {
+    pub const USAGE: [&'static str; 6] = [
+        "fill x y z x y z [color]",
+        "replace <color> <color>",
+        "select x y z x y z",
+        "save [name]",
+        "load [name]",
+        "help",
+    ];
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError {
+    Empty,
+    UnknownCommand(String),
+    Usage(&'static str),
+    InvalidNumber(String),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Empty => write!(f, "Type a command, or 'help' to list them"),
+            CommandError::UnknownCommand(name) => {
+                write!(f, "Unknown command '{}', type 'help' to list them", name)
+            }
+            CommandError::Usage(usage) => write!(f, "Usage: {}", usage),
+            CommandError::InvalidNumber(text) => {
+                write!(
+                    f,
+                    "'{}' is not a coordinate, use whole numbers from 0",
+                    text
+                )
+            }
+        }
+    }
+}
+
+pub fn parse_command(text: &str) -> Result<ConsoleCommand, CommandError> {
+    let mut words = text.split_whitespace();
+    let name = words.next().ok_or(CommandError::Empty)?.to_lowercase();
+    let arguments: Vec<&str> = words.collect();
+
+    match name.as_str() {
+        "fill" => {
+            let (coords, color) = match arguments.as_slice() {
+                [coords @ .., color] if coords.len() == 6 => (coords, Some(color.to_string())),
+                coords if coords.len() == 6 => (coords, None),
+                _ => return Err(CommandError::Usage(ConsoleCommand::USAGE[0])),
+            };
+
+            Ok(ConsoleCommand::Fill {
+                start: parse_coordinate(&coords[..3])?,
+                end: parse_coordinate(&coords[3..])?,
+                color,
+            })
+        }
+        "replace" => match arguments.as_slice() {
+            [from, to] => Ok(ConsoleCommand::Replace {
+                from: from.to_string(),
+                to: to.to_string(),
+            }),
+            _ => Err(CommandError::Usage(ConsoleCommand::USAGE[1])),
+        },
+        "select" if arguments.len() == 6 => Ok(ConsoleCommand::Select {
+            start: parse_coordinate(&arguments[..3])?,
+            end: parse_coordinate(&arguments[3..])?,
+        }),
+        "select" => Err(CommandError::Usage(ConsoleCommand::USAGE[2])),
+        "save" => match arguments.as_slice() {
+            [] => Ok(ConsoleCommand::Save(None)),
+            [name] => Ok(ConsoleCommand::Save(Some(name.to_string()))),
+            _ => Err(CommandError::Usage(ConsoleCommand::USAGE[3])),
+        },
+        "load" => match arguments.as_slice() {
+            [] => Ok(ConsoleCommand::Load(None)),
+            [name] => Ok(ConsoleCommand::Load(Some(name.to_string()))),
+            _ => Err(CommandError::Usage(ConsoleCommand::USAGE[4])),
+        },
+        "help" => Ok(ConsoleCommand::Help),
+        _ => Err(CommandError::UnknownCommand(name)),
+    }
+}
+
+/// Reads the first three values as x, y and z.
+fn parse_coordinate(values: &[&str]) -> Result<Coordinate, CommandError> {
+    let parse = |value: &str| {
+        value
+            .parse::<usize>()
+            .map_err(|_| CommandError::InvalidNumber(value.to_string()))
+    };
+
+    Ok(Coordinate::new(
+        parse(values[0])?,
+        parse(values[1])?,
+        parse(values[2])?,
+    ))
+}
+
+/// Finds a palette entry by its name, or by a color name like `red` or a hex color like `#44aa22`.
+pub fn find_palette_index(name: &str, color_library: &ColorLibrary) -> Option<usize> {
+    palette_index_by_name(name, color_library)
+        .or_else(|| parse_color(name).and_then(|color| color_library.index_of_color(color)))
+}
+
+/// Like `find_palette_index`, colors that aren't in the palette yet are added to it.
+pub fn palette_index_or_add(name: &str, color_library: &mut ColorLibrary) -> Option<usize> {
+    palette_index_by_name(name, color_library)
+        .or_else(|| parse_color(name).and_then(|color| color_library.index_of_or_add(color)))
+}
+
+fn palette_index_by_name(name: &str, color_library: &ColorLibrary) -> Option<usize> {
+    (0..color_library.count()).find(|index| {
+        color_library.color_name(*index).is_some_and(|color_name| {
+            !color_name.is_empty() && color_name.eq_ignore_ascii_case(name)
+        })
+    })
+}
+
+fn parse_color(name: &str) -> Option<Color> {
+    NAMED_COLORS
+        .iter()
+        .find(|(color_name, _)| color_name.eq_ignore_ascii_case(name))
+        .map(|(_, color)| *color)
+        .or_else(|| color_from_hex(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_fill_with_and_without_color() {
+        assert_eq!(
+            parse_command("fill 0 0 0 10 2 10 #44aa22"),
+            Ok(ConsoleCommand::Fill {
+                start: Coordinate::new(0, 0, 0),
+                end: Coordinate::new(10, 2, 10),
+                color: Some("#44aa22".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_command("  FILL 1 2 3 4 5 6 "),
+            Ok(ConsoleCommand::Fill {
+                start: Coordinate::new(1, 2, 3),
+                end: Coordinate::new(4, 5, 6),
+                color: None,
+            })
+        );
+    }
+
+    #[test]
+    fn wrong_arguments_show_the_usage() {
+        assert_eq!(
+            parse_command("fill 0 0 0"),
+            Err(CommandError::Usage(ConsoleCommand::USAGE[0]))
+        );
+        assert_eq!(
+            parse_command("select 0 0 0 5 -5 5"),
+            Err(CommandError::InvalidNumber("-5".to_string()))
+        );
+        assert_eq!(
+            parse_command("save my castle"),
+            Err(CommandError::Usage(ConsoleCommand::USAGE[3]))
+        );
+        assert_eq!(
+            parse_command("build"),
+            Err(CommandError::UnknownCommand("build".to_string()))
+        );
+        assert_eq!(parse_command("   "), Err(CommandError::Empty));
+    }
+
+    #[test]
+    fn can_parse_replace_save_and_select() {
+        assert_eq!(
+            parse_command("replace red blue"),
+            Ok(ConsoleCommand::Replace {
+                from: "red".to_string(),
+                to: "blue".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_command("save castle"),
+            Ok(ConsoleCommand::Save(Some("castle".to_string())))
+        );
+        assert_eq!(parse_command("load"), Ok(ConsoleCommand::Load(None)));
+        assert_eq!(
+            parse_command("select 0 0 0 5 5 5"),
+            Ok(ConsoleCommand::Select {
+                start: Coordinate::new(0, 0, 0),
+                end: Coordinate::new(5, 5, 5),
+            })
+        );
+    }
+
+    #[test]
+    fn colors_are_found_by_palette_name_color_name_or_hex() {
+        let mut color_library = ColorLibrary::with_default_colors();
+        color_library.rename_color(2, "Stone");
+
+        assert_eq!(find_palette_index("stone", &color_library), Some(2));
+        assert_eq!(find_palette_index("cyan", &color_library), Some(1));
+        assert_eq!(find_palette_index("red", &color_library), None);
+        assert_eq!(find_palette_index("#nothex", &color_library), None);
+
+        let added = palette_index_or_add("#44aa22", &mut color_library);
+
+        assert_eq!(added, Some(3));
+        assert_eq!(color_library.color_at(3), Some(Color::rgb_u8(68, 170, 34)));
+    }
+}
@@ -0,0 +1,118 @@
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use ron::ser::PrettyConfig;
+use serde::{de::DeserializeOwned, Serialize};
+
+/*
+    Config files are RON files that are written with their defaults on the first start,
+    so they can be edited by hand afterwards.
+
+    They live in the user's config directory instead of the project, so personal settings
+    don't end up in the repository.
+*/
+
+const APP_DIRECTORY: &str = "voxel_editor";
+const FALLBACK_DIRECTORY: &str = "assets/config";
+
+#[derive(Debug)]
+pub enum ConfigFileError {
+    Io(io::Error),
+    Ron(ron::Error),
+}
+
+impl From<io::Error> for ConfigFileError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ron::Error> for ConfigFileError {
+    fn from(error: ron::Error) -> Self {
+        Self::Ron(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for ConfigFileError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        Self::Ron(error.into())
+    }
+}
+
+impl fmt::Display for ConfigFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigFileError::Io(error) => write!(f, "{}", error),
+            ConfigFileError::Ron(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+pub fn config_path(file_name: &str) -> PathBuf {
+    config_directory().join(file_name)
+}
+
+/// The platform's per-user config directory, or `assets/config` if it can't be found.
+fn config_directory() -> PathBuf {
+    let user_config = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+
+    match user_config {
+        Some(directory) => directory.join(APP_DIRECTORY),
+        None => PathBuf::from(FALLBACK_DIRECTORY),
+    }
+}
+
+/// Reads and parses the config file, or writes the defaults to it if there is none yet.
+///
+/// Returns `None` when the defaults are used, errors are printed with what is used instead.
+pub fn load_or_write_defaults<T, E: fmt::Display + From<ConfigFileError>>(
+    path: &Path,
+    description: &str,
+    write_defaults: impl FnOnce(&Path) -> Result<(), E>,
+    parse: impl FnOnce(&str) -> Result<T, E>,
+) -> Option<T> {
+    if !path.exists() {
+        match write_defaults(path) {
+            Ok(()) => println!("Wrote default {} to {}", description, path.display()),
+            Err(error) => println!("Error while writing default {}: {}", description, error),
+        }
+
+        return None;
+    }
+
+    let loaded = fs::read_to_string(path)
+        .map_err(|error| E::from(error.into()))
+        .and_then(|file| parse(&file));
+
+    match loaded {
+        Ok(loaded) => Some(loaded),
+        Err(error) => {
+            println!(
+                "Error in {}: {}. Using the default {}",
+                path.display(),
+                error,
+                description
+            );
+            None
+        }
+    }
+}
+
+/// Writes the value as readable RON, creating the config directory if needed.
+pub fn save_config<T: Serialize>(value: &T, path: &Path) -> Result<(), ConfigFileError> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+
+    let serialized = ron::ser::to_string_pretty(value, PrettyConfig::default())?;
+    Ok(fs::write(path, serialized)?)
+}
+
+pub fn parse_config<T: DeserializeOwned>(file: &str) -> Result<T, ConfigFileError> {
+    Ok(ron::from_str::<T>(file)?)
+}
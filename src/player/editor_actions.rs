@@ -0,0 +1,645 @@
+pub mod keybinding_config;
+
+use bevy::{
+    input::InputSystem,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::game_systems::text_field::capture_text_input;
+
+use self::keybinding_config::KeybindingConfigPlugin;
+use super::{
+    editor_modes::EditorMode,
+    mouse_interaction::{update_mouse_on_ui, MouseInteraction},
+};
+
+/*
+    Input is handled in 3 layers, following the notes in `mouse_events.rs`:
+    - `EditorAction` describes what happens, not which key was used.
+    - `InputMap` maps keys and buttons to actions, with requirements like the current editor mode.
+        Bindings can be chords like Ctrl+S, the binding with the most matching modifiers wins.
+    - Systems respond to `OnEditorAction` events, or check `ActionState` for actions that are held.
+
+    The bindings are loaded from a config file at startup, see `keybinding_config.rs`.
+*/
+
+pub struct EditorActionsPlugin;
+
+impl Plugin for EditorActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(KeybindingConfigPlugin)
+            .add_event::<OnEditorAction>()
+            .init_resource::<InputMap>()
+            .init_resource::<ActionState>()
+            .add_systems(
+                PreUpdate,
+                send_editor_actions
+                    .after(InputSystem)
+                    .after(capture_text_input)
+                    .after(update_mouse_on_ui),
+            );
+    }
+}
+
+/// Number keys 1 - 9 select the first nine colors in the library.
+const HOTBAR_KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+/// Everything the user can do with a key or button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EditorAction {
+    SwitchEditorMode,
+    SaveScene,
+    LoadScene,
+    /// Builds, selects or paints with the current tool.
+    UseTool,
+    /// Held to make build tools remove blocks.
+    Remove,
+    PickColor,
+    ClearSelection,
+    SwitchBuildTool,
+    SwitchBuildShape,
+    SwitchBlockShape,
+    SwitchBrushShape,
+    GrowBrush,
+    ShrinkBrush,
+    ReplaceColor,
+    /// Held to also replace similar colors.
+    LooseReplace,
+    TogglePicker,
+    TogglePaletteEditor,
+    ToggleSettings,
+    ToggleConsole,
+    SelectHotbarColor(usize),
+    /// Held to cycle colors with the scroll wheel.
+    CycleColors,
+    /// Places a block at the target, for inputs that don't make mouse gestures.
+    PlaceBlock,
+    /// Removes the targeted block, for inputs that don't make mouse gestures.
+    RemoveBlock,
+    NextColor,
+    PreviousColor,
+    SwitchDragConstraint,
+    /// Held to lock a build drag to its longest axis.
+    LockDragAxis,
+    /// Moves the end of a build drag one step along the start face normal.
+    RaiseDrag,
+    LowerDrag,
+    /// Moves the construction grid one layer up, it's the target when no block is hit.
+    RaiseGrid,
+    LowerGrid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputButton {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    /// The button on any connected gamepad.
+    Gamepad(GamepadButtonType),
+    /// Either the left or right key of a modifier.
+    Modifier(Modifier),
+}
+
+/// Keys that are held to make chords, the left and right keys count as the same modifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Modifier {
+    Control,
+    Shift,
+    Alt,
+}
+
+impl Modifier {
+    pub const ALL: [Modifier; 3] = [Modifier::Control, Modifier::Shift, Modifier::Alt];
+
+    pub fn keys(&self) -> [KeyCode; 2] {
+        match self {
+            Modifier::Control => [KeyCode::ControlLeft, KeyCode::ControlRight],
+            Modifier::Shift => [KeyCode::ShiftLeft, KeyCode::ShiftRight],
+            Modifier::Alt => [KeyCode::AltLeft, KeyCode::AltRight],
+        }
+    }
+
+    /// The modifiers that have at least one of their keys held.
+    pub fn held(keys: &Input<KeyCode>) -> Vec<Modifier> {
+        Modifier::ALL
+            .into_iter()
+            .filter(|modifier| keys.any_pressed(modifier.keys()))
+            .collect()
+    }
+}
+
+impl From<KeyCode> for InputButton {
+    fn from(value: KeyCode) -> Self {
+        InputButton::Key(value)
+    }
+}
+
+impl From<MouseButton> for InputButton {
+    fn from(value: MouseButton) -> Self {
+        InputButton::Mouse(value)
+    }
+}
+
+impl From<GamepadButtonType> for InputButton {
+    fn from(value: GamepadButtonType) -> Self {
+        InputButton::Gamepad(value)
+    }
+}
+
+impl From<Modifier> for InputButton {
+    fn from(value: Modifier) -> Self {
+        InputButton::Modifier(value)
+    }
+}
+
+/// World state that has to match before an action is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActionRequirement {
+    CursorOffUi,
+    InMode(EditorMode),
+}
+
+/// The state that requirements are checked against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActionContext {
+    pub cursor_on_ui: bool,
+    pub editor_mode: EditorMode,
+}
+
+impl ActionRequirement {
+    fn is_met(&self, context: &ActionContext) -> bool {
+        match self {
+            ActionRequirement::CursorOffUi => !context.cursor_on_ui,
+            ActionRequirement::InMode(mode) => context.editor_mode == *mode,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionBinding {
+    pub input: InputButton,
+    pub action: EditorAction,
+    /// Modifiers that have to be held with the input, sorted so chords can be compared.
+    pub modifiers: Vec<Modifier>,
+    pub requirements: Vec<ActionRequirement>,
+}
+
+impl ActionBinding {
+    pub fn new(input: impl Into<InputButton>, action: EditorAction) -> Self {
+        Self {
+            input: input.into(),
+            action,
+            modifiers: Vec::new(),
+            requirements: Vec::new(),
+        }
+    }
+
+    pub fn with_modifier(mut self, modifier: Modifier) -> Self {
+        if !self.modifiers.contains(&modifier) {
+            self.modifiers.push(modifier);
+            self.modifiers.sort();
+        }
+        self
+    }
+
+    pub fn with_requirement(mut self, requirement: ActionRequirement) -> Self {
+        self.requirements.push(requirement);
+        self
+    }
+
+    pub fn is_chord(&self) -> bool {
+        !self.modifiers.is_empty()
+    }
+
+    /// Bindings without modifiers ignore them, chords need exactly their modifiers held.
+    fn modifiers_match(&self, held_modifiers: &[Modifier]) -> bool {
+        !self.is_chord()
+            || Modifier::ALL.iter().all(|modifier| {
+                self.modifiers.contains(modifier) == held_modifiers.contains(modifier)
+            })
+    }
+
+    fn requirements_met(&self, context: &ActionContext) -> bool {
+        self.requirements
+            .iter()
+            .all(|requirement| requirement.is_met(context))
+    }
+}
+
+/// All key and button bindings, an input can trigger multiple actions and an action can have multiple inputs.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct InputMap {
+    bindings: Vec<ActionBinding>,
+}
+
+impl InputMap {
+    pub fn new(bindings: Vec<ActionBinding>) -> Self {
+        Self { bindings }
+    }
+
+    pub fn bindings(&self) -> &[ActionBinding] {
+        &self.bindings
+    }
+
+    /// Returns true if the input is bound to the action, without checking requirements.
+    pub fn is_bound(&self, action: EditorAction, input: impl Into<InputButton>) -> bool {
+        let input = input.into();
+
+        self.bindings
+            .iter()
+            .any(|binding| binding.action == action && binding.input == input)
+    }
+
+    /// Returns every action that has an active input and meets its requirements.
+    ///
+    /// When a chord matches, bindings without modifiers on the same input are skipped, so Ctrl+S doesn't also trigger S.
+    pub fn active_actions(
+        &self,
+        context: &ActionContext,
+        held_modifiers: &[Modifier],
+        is_active: impl Fn(InputButton) -> bool,
+    ) -> Vec<EditorAction> {
+        let matching: Vec<&ActionBinding> = self
+            .bindings
+            .iter()
+            .filter(|binding| {
+                is_active(binding.input)
+                    && binding.modifiers_match(held_modifiers)
+                    && binding.requirements_met(context)
+            })
+            .collect();
+
+        let mut actions: Vec<EditorAction> = Vec::new();
+
+        for binding in matching.iter() {
+            let replaced_by_chord = !binding.is_chord()
+                && matching
+                    .iter()
+                    .any(|other| other.is_chord() && other.input == binding.input);
+
+            if !replaced_by_chord && !actions.contains(&binding.action) {
+                actions.push(binding.action);
+            }
+        }
+
+        actions
+    }
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        use ActionRequirement::*;
+        use EditorAction::*;
+
+        let mut bindings = vec![
+            ActionBinding::new(KeyCode::M, SwitchEditorMode),
+            ActionBinding::new(KeyCode::I, SaveScene),
+            ActionBinding::new(KeyCode::S, SaveScene).with_modifier(Modifier::Control),
+            ActionBinding::new(KeyCode::O, LoadScene),
+            ActionBinding::new(KeyCode::O, LoadScene).with_modifier(Modifier::Control),
+            ActionBinding::new(MouseButton::Left, UseTool).with_requirement(CursorOffUi),
+            ActionBinding::new(Modifier::Shift, Remove),
+            ActionBinding::new(MouseButton::Middle, PickColor).with_requirement(CursorOffUi),
+            ActionBinding::new(MouseButton::Left, PickColor)
+                .with_modifier(Modifier::Alt)
+                .with_requirement(CursorOffUi),
+            ActionBinding::new(KeyCode::Delete, ClearSelection)
+                .with_requirement(InMode(EditorMode::Select)),
+            ActionBinding::new(KeyCode::B, SwitchBuildTool)
+                .with_requirement(InMode(EditorMode::Build)),
+            ActionBinding::new(KeyCode::T, SwitchBuildShape)
+                .with_requirement(InMode(EditorMode::Build)),
+            ActionBinding::new(KeyCode::V, SwitchBlockShape)
+                .with_requirement(InMode(EditorMode::Build)),
+            ActionBinding::new(KeyCode::G, SwitchDragConstraint)
+                .with_requirement(InMode(EditorMode::Build)),
+            ActionBinding::new(Modifier::Control, LockDragAxis),
+            ActionBinding::new(KeyCode::PageUp, RaiseDrag)
+                .with_requirement(InMode(EditorMode::Build)),
+            ActionBinding::new(KeyCode::PageDown, LowerDrag)
+                .with_requirement(InMode(EditorMode::Build)),
+            ActionBinding::new(KeyCode::Equals, RaiseGrid),
+            ActionBinding::new(KeyCode::Minus, LowerGrid),
+            ActionBinding::new(KeyCode::N, SwitchBrushShape),
+            ActionBinding::new(KeyCode::BracketRight, GrowBrush),
+            ActionBinding::new(KeyCode::BracketLeft, ShrinkBrush),
+            ActionBinding::new(KeyCode::R, ReplaceColor),
+            ActionBinding::new(Modifier::Shift, LooseReplace),
+            ActionBinding::new(KeyCode::P, TogglePicker),
+            ActionBinding::new(KeyCode::L, TogglePaletteEditor),
+            ActionBinding::new(KeyCode::Comma, ToggleSettings),
+            ActionBinding::new(KeyCode::Grave, ToggleConsole),
+            ActionBinding::new(Modifier::Control, CycleColors),
+            ActionBinding::new(GamepadButtonType::RightTrigger2, PlaceBlock)
+                .with_requirement(InMode(EditorMode::Build)),
+            ActionBinding::new(GamepadButtonType::LeftTrigger2, RemoveBlock)
+                .with_requirement(InMode(EditorMode::Build)),
+            ActionBinding::new(GamepadButtonType::RightTrigger, NextColor),
+            ActionBinding::new(GamepadButtonType::LeftTrigger, PreviousColor),
+        ];
+
+        for (index, key) in HOTBAR_KEYS.into_iter().enumerate() {
+            bindings.push(ActionBinding::new(key, SelectHotbarColor(index)));
+        }
+
+        Self::new(bindings)
+    }
+}
+
+/// Sent on the frame an action's input is pressed.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct OnEditorAction {
+    pub action: EditorAction,
+}
+
+impl OnEditorAction {
+    pub fn new(action: EditorAction) -> Self {
+        Self { action }
+    }
+}
+
+/// The actions whose inputs are currently held down.
+#[derive(Resource, Debug, Default)]
+pub struct ActionState {
+    held: HashSet<EditorAction>,
+    /// The actions each mouse button triggered when it was last pressed, these stay until the next press.
+    button_actions: HashMap<MouseButton, Vec<EditorAction>>,
+}
+
+impl ActionState {
+    pub fn is_held(&self, action: EditorAction) -> bool {
+        self.held.contains(&action)
+    }
+
+    /// Returns true if the last press of this button triggered the action.
+    ///
+    /// Mouse gestures are handled after the button is released, this keeps the chord that started them.
+    pub fn button_triggered(&self, action: EditorAction, button: MouseButton) -> bool {
+        self.button_actions
+            .get(&button)
+            .is_some_and(|actions| actions.contains(&action))
+    }
+}
+
+/// Reads all action events and returns true if the action was one of them,
+/// reading every event keeps the others from showing up again next frame.
+pub fn action_triggered(on_action: &mut EventReader<OnEditorAction>, action: EditorAction) -> bool {
+    on_action
+        .iter()
+        .filter(|event| event.action == action)
+        .count()
+        > 0
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn send_editor_actions(
+    keys: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mouse_interaction: Res<MouseInteraction>,
+    editor_mode: Res<State<EditorMode>>,
+    input_map: Res<InputMap>,
+    mut action_state: ResMut<ActionState>,
+    mut on_action: EventWriter<OnEditorAction>,
+) {
+    let context = ActionContext {
+        cursor_on_ui: mouse_interaction.mouse_on_ui(),
+        editor_mode: *editor_mode.get(),
+    };
+
+    let held_modifiers = Modifier::held(&keys);
+
+    let held = input_map.active_actions(&context, &held_modifiers, |input| match input {
+        InputButton::Key(key) => keys.pressed(key),
+        InputButton::Mouse(button) => mouse_buttons.pressed(button),
+        InputButton::Gamepad(button_type) => gamepads
+            .iter()
+            .any(|gamepad| gamepad_buttons.pressed(GamepadButton::new(gamepad, button_type))),
+        InputButton::Modifier(modifier) => keys.any_pressed(modifier.keys()),
+    });
+
+    let just_pressed = input_map.active_actions(&context, &held_modifiers, |input| match input {
+        InputButton::Key(key) => keys.just_pressed(key),
+        InputButton::Mouse(button) => mouse_buttons.just_pressed(button),
+        InputButton::Gamepad(button_type) => gamepads
+            .iter()
+            .any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, button_type))),
+        InputButton::Modifier(modifier) => keys.any_just_pressed(modifier.keys()),
+    });
+
+    for button in mouse_buttons.get_just_pressed() {
+        let button_actions = input_map.active_actions(&context, &held_modifiers, |input| {
+            input == InputButton::Mouse(*button)
+        });
+
+        action_state.button_actions.insert(*button, button_actions);
+    }
+
+    action_state.held = held.into_iter().collect();
+
+    for action in just_pressed {
+        on_action.send(OnEditorAction::new(action));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::gamepad::{GamepadButtonChangedEvent, GamepadEvent};
+
+    use crate::player::test_input::{connect_gamepad, input_app};
+
+    use super::*;
+
+    const BUILD_CONTEXT: ActionContext = ActionContext {
+        cursor_on_ui: false,
+        editor_mode: EditorMode::Build,
+    };
+
+    #[test]
+    fn one_input_can_trigger_multiple_actions() {
+        let input_map = InputMap::default();
+
+        let actions = input_map.active_actions(&BUILD_CONTEXT, &[], |input| {
+            input == InputButton::Modifier(Modifier::Shift)
+        });
+
+        assert_eq!(
+            actions,
+            vec![EditorAction::Remove, EditorAction::LooseReplace]
+        );
+    }
+
+    #[test]
+    fn actions_wait_for_their_editor_mode() {
+        let input_map = InputMap::default();
+        let is_delete = |input| input == InputButton::Key(KeyCode::Delete);
+
+        let select_context = ActionContext {
+            editor_mode: EditorMode::Select,
+            ..BUILD_CONTEXT
+        };
+
+        assert!(input_map
+            .active_actions(&BUILD_CONTEXT, &[], is_delete)
+            .is_empty());
+        assert_eq!(
+            input_map.active_actions(&select_context, &[], is_delete),
+            vec![EditorAction::ClearSelection]
+        );
+    }
+
+    #[test]
+    fn clicks_on_ui_dont_use_tools() {
+        let input_map = InputMap::default();
+        let ui_context = ActionContext {
+            cursor_on_ui: true,
+            ..BUILD_CONTEXT
+        };
+
+        let actions = input_map.active_actions(&ui_context, &[], |input| {
+            input == InputButton::Mouse(MouseButton::Left)
+        });
+
+        assert!(actions.is_empty());
+    }
+
+    #[test]
+    fn action_with_multiple_inputs_is_listed_once() {
+        let input_map = InputMap::new(vec![
+            ActionBinding::new(KeyCode::Delete, EditorAction::ClearSelection),
+            ActionBinding::new(KeyCode::Back, EditorAction::ClearSelection),
+        ]);
+
+        let actions = input_map.active_actions(&BUILD_CONTEXT, &[], |_| true);
+
+        assert_eq!(actions, vec![EditorAction::ClearSelection]);
+        assert!(input_map.is_bound(EditorAction::ClearSelection, KeyCode::Back));
+        assert!(!input_map.is_bound(EditorAction::ClearSelection, MouseButton::Left));
+    }
+
+    #[test]
+    fn hotbar_keys_select_their_slot() {
+        let input_map = InputMap::default();
+
+        let actions = input_map.active_actions(&BUILD_CONTEXT, &[], |input| {
+            input == InputButton::Key(KeyCode::Key3)
+        });
+
+        assert_eq!(actions, vec![EditorAction::SelectHotbarColor(2)]);
+    }
+
+    #[test]
+    fn left_and_right_modifier_keys_are_the_same() {
+        let mut keys = Input::<KeyCode>::default();
+        keys.press(KeyCode::ShiftRight);
+        keys.press(KeyCode::AltLeft);
+
+        assert_eq!(Modifier::held(&keys), vec![Modifier::Shift, Modifier::Alt]);
+    }
+
+    #[test]
+    fn chord_replaces_binding_without_modifiers() {
+        let input_map = InputMap::new(vec![
+            ActionBinding::new(KeyCode::S, EditorAction::SwitchBrushShape),
+            ActionBinding::new(KeyCode::S, EditorAction::SaveScene)
+                .with_modifier(Modifier::Control),
+        ]);
+        let is_s = |input| input == InputButton::Key(KeyCode::S);
+
+        assert_eq!(
+            input_map.active_actions(&BUILD_CONTEXT, &[], is_s),
+            vec![EditorAction::SwitchBrushShape]
+        );
+        assert_eq!(
+            input_map.active_actions(&BUILD_CONTEXT, &[Modifier::Control], is_s),
+            vec![EditorAction::SaveScene]
+        );
+    }
+
+    #[test]
+    fn chords_need_exactly_their_modifiers() {
+        let input_map = InputMap::new(vec![
+            ActionBinding::new(KeyCode::Z, EditorAction::ShrinkBrush)
+                .with_modifier(Modifier::Control),
+            ActionBinding::new(KeyCode::Z, EditorAction::GrowBrush)
+                .with_modifier(Modifier::Shift)
+                .with_modifier(Modifier::Control),
+        ]);
+        let is_z = |input| input == InputButton::Key(KeyCode::Z);
+
+        assert_eq!(
+            input_map.active_actions(&BUILD_CONTEXT, &[Modifier::Control, Modifier::Shift], is_z),
+            vec![EditorAction::GrowBrush]
+        );
+        assert!(input_map
+            .active_actions(&BUILD_CONTEXT, &[Modifier::Alt], is_z)
+            .is_empty());
+    }
+
+    #[test]
+    fn alt_click_picks_color_instead_of_using_tool() {
+        let input_map = InputMap::default();
+        let is_left_click = |input| input == InputButton::Mouse(MouseButton::Left);
+
+        assert_eq!(
+            input_map.active_actions(&BUILD_CONTEXT, &[Modifier::Alt], is_left_click),
+            vec![EditorAction::PickColor]
+        );
+        assert_eq!(
+            input_map.active_actions(&BUILD_CONTEXT, &[Modifier::Shift], is_left_click),
+            vec![EditorAction::UseTool]
+        );
+    }
+
+    #[test]
+    fn gamepad_buttons_send_actions() {
+        let mut app = input_app();
+        app.add_state::<EditorMode>()
+            .add_event::<OnEditorAction>()
+            .init_resource::<MouseInteraction>()
+            .init_resource::<InputMap>()
+            .init_resource::<ActionState>()
+            .add_systems(PreUpdate, send_editor_actions.after(InputSystem));
+
+        let gamepad = Gamepad::new(0);
+        connect_gamepad(&mut app, gamepad);
+
+        app.world.send_event_batch([
+            GamepadEvent::Button(GamepadButtonChangedEvent::new(
+                gamepad,
+                GamepadButtonType::RightTrigger2,
+                1.0,
+            )),
+            GamepadEvent::Button(GamepadButtonChangedEvent::new(
+                gamepad,
+                GamepadButtonType::LeftTrigger,
+                1.0,
+            )),
+        ]);
+        app.update();
+
+        let events = app.world.resource::<Events<OnEditorAction>>();
+        let actions: Vec<EditorAction> = events
+            .get_reader()
+            .iter(events)
+            .map(|event| event.action)
+            .collect();
+
+        assert_eq!(
+            actions,
+            vec![EditorAction::PlaceBlock, EditorAction::PreviousColor]
+        );
+    }
+}
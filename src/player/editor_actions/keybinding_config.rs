@@ -0,0 +1,685 @@
+use std::{fmt, path::Path};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::player::{
+    camera::CAMERA_MOVEMENT_KEYS,
+    config_file::{
+        config_path, load_or_write_defaults, parse_config, save_config, ConfigFileError,
+    },
+};
+
+use super::{ActionBinding, ActionRequirement, EditorAction, InputButton, InputMap, Modifier};
+
+pub struct KeybindingConfigPlugin;
+
+impl Plugin for KeybindingConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_keybindings);
+    }
+}
+
+const KEYBINDINGS_FILE: &str = "keybindings.ron";
+
+/// Keys that can be used in the config, they are written with the same name as their `KeyCode` variant.
+const NAMED_KEYS: [KeyCode; 98] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::Key0,
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::Escape,
+    KeyCode::Insert,
+    KeyCode::Home,
+    KeyCode::Delete,
+    KeyCode::End,
+    KeyCode::PageDown,
+    KeyCode::PageUp,
+    KeyCode::Left,
+    KeyCode::Up,
+    KeyCode::Right,
+    KeyCode::Down,
+    KeyCode::Back,
+    KeyCode::Return,
+    KeyCode::Space,
+    KeyCode::Tab,
+    KeyCode::Numpad0,
+    KeyCode::Numpad1,
+    KeyCode::Numpad2,
+    KeyCode::Numpad3,
+    KeyCode::Numpad4,
+    KeyCode::Numpad5,
+    KeyCode::Numpad6,
+    KeyCode::Numpad7,
+    KeyCode::Numpad8,
+    KeyCode::Numpad9,
+    KeyCode::NumpadAdd,
+    KeyCode::NumpadSubtract,
+    KeyCode::NumpadMultiply,
+    KeyCode::NumpadDivide,
+    KeyCode::NumpadDecimal,
+    KeyCode::NumpadEnter,
+    KeyCode::Apostrophe,
+    KeyCode::Backslash,
+    KeyCode::Comma,
+    KeyCode::Equals,
+    KeyCode::Grave,
+    KeyCode::Minus,
+    KeyCode::Period,
+    KeyCode::Semicolon,
+    KeyCode::Slash,
+    KeyCode::BracketLeft,
+    KeyCode::BracketRight,
+    KeyCode::AltLeft,
+    KeyCode::AltRight,
+    KeyCode::ControlLeft,
+    KeyCode::ControlRight,
+    KeyCode::ShiftLeft,
+    KeyCode::ShiftRight,
+    KeyCode::SuperLeft,
+    KeyCode::SuperRight,
+];
+
+/// Written before the input with a '+', like "Ctrl+Shift+Z", or on their own to bind both keys of the modifier.
+const NAMED_MODIFIERS: [(Modifier, &str); 3] = [
+    (Modifier::Control, "Ctrl"),
+    (Modifier::Shift, "Shift"),
+    (Modifier::Alt, "Alt"),
+];
+
+const NAMED_MOUSE_BUTTONS: [(MouseButton, &str); 3] = [
+    (MouseButton::Left, "MouseLeft"),
+    (MouseButton::Right, "MouseRight"),
+    (MouseButton::Middle, "MouseMiddle"),
+];
+
+/// Bevy calls the bumpers `LeftTrigger` and `RightTrigger`, these names follow the labels on the gamepad instead.
+const NAMED_GAMEPAD_BUTTONS: [(GamepadButtonType, &str); 16] = [
+    (GamepadButtonType::South, "GamepadSouth"),
+    (GamepadButtonType::East, "GamepadEast"),
+    (GamepadButtonType::North, "GamepadNorth"),
+    (GamepadButtonType::West, "GamepadWest"),
+    (GamepadButtonType::LeftTrigger, "GamepadLeftBumper"),
+    (GamepadButtonType::RightTrigger, "GamepadRightBumper"),
+    (GamepadButtonType::LeftTrigger2, "GamepadLeftTrigger"),
+    (GamepadButtonType::RightTrigger2, "GamepadRightTrigger"),
+    (GamepadButtonType::Select, "GamepadSelect"),
+    (GamepadButtonType::Start, "GamepadStart"),
+    (GamepadButtonType::LeftThumb, "GamepadLeftStick"),
+    (GamepadButtonType::RightThumb, "GamepadRightStick"),
+    (GamepadButtonType::DPadUp, "GamepadDPadUp"),
+    (GamepadButtonType::DPadDown, "GamepadDPadDown"),
+    (GamepadButtonType::DPadLeft, "GamepadDPadLeft"),
+    (GamepadButtonType::DPadRight, "GamepadDPadRight"),
+];
+
+/// The keybindings file, inputs are stored by name so they are easy to edit by hand.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct KeybindingConfig {
+    /// The actions that existed when the file was written, only newer actions get their default binding added.
+    /// This way a binding that was removed from the file stays removed.
+    #[serde(default)]
+    known_actions: Vec<EditorAction>,
+    bindings: Vec<BindingConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct BindingConfig {
+    /// A key or button name, optionally with modifiers like "Ctrl+S".
+    input: String,
+    action: EditorAction,
+    #[serde(default)]
+    requirements: Vec<ActionRequirement>,
+}
+
+#[derive(Debug)]
+pub enum KeybindingError {
+    File(ConfigFileError),
+    UnknownInput(String),
+    /// Two actions on the same input that could be triggered at the same time.
+    Conflict {
+        input: String,
+        first: EditorAction,
+        second: EditorAction,
+    },
+    /// A camera movement key used without Ctrl or Alt.
+    CameraKey {
+        input: String,
+        action: EditorAction,
+    },
+}
+
+impl From<ConfigFileError> for KeybindingError {
+    fn from(error: ConfigFileError) -> Self {
+        Self::File(error)
+    }
+}
+
+impl fmt::Display for KeybindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeybindingError::File(error) => write!(f, "{}", error),
+            KeybindingError::UnknownInput(name) => write!(
+                f,
+                "unknown key '{}', use a key name like 'A', 'Key1', 'ShiftLeft' or 'MouseLeft'",
+                name
+            ),
+            KeybindingError::Conflict {
+                input,
+                first,
+                second,
+            } => write!(
+                f,
+                "'{}' is bound to both {:?} and {:?}, give one of them another key or an editor mode",
+                input, first, second
+            ),
+            KeybindingError::CameraKey { input, action } => write!(
+                f,
+                "'{}' moves the camera, bind {:?} to another key or hold Ctrl or Alt with it, like 'Ctrl+{}'",
+                input, action, input
+            ),
+        }
+    }
+}
+
+/// Loads the keybindings file, or writes the default bindings to it if there is none yet.
+fn load_keybindings(mut input_map: ResMut<InputMap>) {
+    let path = config_path(KEYBINDINGS_FILE);
+
+    let Some((loaded, known_actions)) = load_or_write_defaults(
+        &path,
+        "keybindings",
+        |path| save_keybindings_to_file(&input_map, path),
+        parse_keybindings,
+    ) else {
+        return;
+    };
+
+    let (loaded, added_actions) = add_missing_default_bindings(loaded, &known_actions);
+
+    if !added_actions.is_empty() {
+        println!(
+            "Added default keybindings for {:?} to {}",
+            added_actions,
+            path.display()
+        );
+    }
+
+    let has_new_actions = default_actions()
+        .iter()
+        .any(|action| !known_actions.contains(action));
+
+    if has_new_actions {
+        if let Err(error) = save_keybindings_to_file(&loaded, &path) {
+            println!("Error while writing keybindings: {}", error);
+        }
+    }
+
+    *input_map = loaded;
+}
+
+fn save_keybindings_to_file(input_map: &InputMap, path: &Path) -> Result<(), KeybindingError> {
+    Ok(save_config(&to_config(input_map), path)?)
+}
+
+/// Returns the bindings in the file and the actions that existed when it was written.
+fn parse_keybindings(file: &str) -> Result<(InputMap, Vec<EditorAction>), KeybindingError> {
+    let config = parse_config::<KeybindingConfig>(file)?;
+
+    let bindings = config
+        .bindings
+        .into_iter()
+        .map(|binding| {
+            let (modifiers, input) = parse_chord(&binding.input)?;
+
+            Ok(ActionBinding {
+                input,
+                action: binding.action,
+                modifiers,
+                requirements: binding.requirements,
+            })
+        })
+        .collect::<Result<Vec<ActionBinding>, KeybindingError>>()?;
+
+    validate_bindings(&bindings)?;
+
+    Ok((InputMap::new(bindings), config.known_actions))
+}
+
+/// Adds the default bindings of actions that were added after the file was written, so they can be used.
+///
+/// Defaults that would conflict with a binding from the file are left out. Returns the actions that got a binding.
+fn add_missing_default_bindings(
+    input_map: InputMap,
+    known_actions: &[EditorAction],
+) -> (InputMap, Vec<EditorAction>) {
+    let mut bindings = input_map.bindings().to_vec();
+    let mut added_actions: Vec<EditorAction> = Vec::new();
+
+    for default in InputMap::default().bindings() {
+        let action_in_file = known_actions.contains(&default.action)
+            || input_map
+                .bindings()
+                .iter()
+                .any(|binding| binding.action == default.action);
+
+        if action_in_file
+            || bindings
+                .iter()
+                .any(|binding| bindings_conflict(binding, default))
+        {
+            continue;
+        }
+
+        bindings.push(default.clone());
+
+        if !added_actions.contains(&default.action) {
+            added_actions.push(default.action);
+        }
+    }
+
+    (InputMap::new(bindings), added_actions)
+}
+
+/// Every action that has a default binding, in the order of the defaults.
+fn default_actions() -> Vec<EditorAction> {
+    let mut actions: Vec<EditorAction> = Vec::new();
+
+    for binding in InputMap::default().bindings() {
+        if !actions.contains(&binding.action) {
+            actions.push(binding.action);
+        }
+    }
+
+    actions
+}
+
+fn to_config(input_map: &InputMap) -> KeybindingConfig {
+    KeybindingConfig {
+        known_actions: default_actions(),
+        bindings: input_map
+            .bindings()
+            .iter()
+            .map(|binding| BindingConfig {
+                input: chord_name(binding),
+                action: binding.action,
+                requirements: binding.requirements.clone(),
+            })
+            .collect(),
+    }
+}
+
+/// The binding's input with its modifiers in front, like "Ctrl+Shift+Z".
+pub fn chord_name(binding: &ActionBinding) -> String {
+    binding
+        .modifiers
+        .iter()
+        .map(|modifier| input_name(InputButton::Modifier(*modifier)))
+        .chain([input_name(binding.input)])
+        .collect::<Vec<String>>()
+        .join("+")
+}
+
+pub fn input_name(input: InputButton) -> String {
+    match input {
+        InputButton::Key(key) => format!("{:?}", key),
+        InputButton::Modifier(modifier) => NAMED_MODIFIERS
+            .iter()
+            .find(|(named_modifier, _)| *named_modifier == modifier)
+            .map(|(_, name)| name.to_string())
+            .unwrap_or_else(|| format!("{:?}", modifier)),
+        InputButton::Mouse(button) => NAMED_MOUSE_BUTTONS
+            .iter()
+            .find(|(named_button, _)| *named_button == button)
+            .map(|(_, name)| name.to_string())
+            .unwrap_or_else(|| format!("{:?}", button)),
+        InputButton::Gamepad(button_type) => NAMED_GAMEPAD_BUTTONS
+            .iter()
+            .find(|(named_button, _)| *named_button == button_type)
+            .map(|(_, name)| name.to_string())
+            .unwrap_or_else(|| format!("Gamepad{:?}", button_type)),
+    }
+}
+
+/// Splits a name like "Ctrl+Shift+Z" into its modifiers and the input they are held with.
+pub fn parse_chord(name: &str) -> Result<(Vec<Modifier>, InputButton), KeybindingError> {
+    let mut parts: Vec<&str> = name.split('+').collect();
+    let input = parse_input(parts.pop().unwrap_or_default())?;
+
+    let mut modifiers: Vec<Modifier> = Vec::new();
+
+    for part in parts {
+        match parse_input(part)? {
+            InputButton::Modifier(modifier) if !modifiers.contains(&modifier) => {
+                modifiers.push(modifier)
+            }
+            InputButton::Modifier(_) => {}
+            _ => return Err(KeybindingError::UnknownInput(part.trim().to_string())),
+        }
+    }
+
+    modifiers.sort();
+
+    Ok((modifiers, input))
+}
+
+pub fn parse_input(name: &str) -> Result<InputButton, KeybindingError> {
+    let name = name.trim();
+
+    if let Some((modifier, _)) = NAMED_MODIFIERS
+        .iter()
+        .find(|(_, modifier_name)| modifier_name.eq_ignore_ascii_case(name))
+    {
+        return Ok(InputButton::Modifier(*modifier));
+    }
+
+    if let Some((button, _)) = NAMED_MOUSE_BUTTONS
+        .iter()
+        .find(|(_, button_name)| button_name.eq_ignore_ascii_case(name))
+    {
+        return Ok(InputButton::Mouse(*button));
+    }
+
+    if let Some((button_type, _)) = NAMED_GAMEPAD_BUTTONS
+        .iter()
+        .find(|(_, button_name)| button_name.eq_ignore_ascii_case(name))
+    {
+        return Ok(InputButton::Gamepad(*button_type));
+    }
+
+    NAMED_KEYS
+        .iter()
+        .find(|key| format!("{:?}", key).eq_ignore_ascii_case(name))
+        .map(|key| InputButton::Key(*key))
+        .ok_or_else(|| KeybindingError::UnknownInput(name.to_string()))
+}
+
+/// Actions that change what other inputs do while they are held, these can share a key.
+fn is_held_action(action: EditorAction) -> bool {
+    matches!(
+        action,
+        EditorAction::Remove
+            | EditorAction::LooseReplace
+            | EditorAction::CycleColors
+            | EditorAction::LockDragAxis
+    )
+}
+
+/// Bindings can only be active at the same time if they don't require different editor modes.
+fn requirements_overlap(first: &[ActionRequirement], second: &[ActionRequirement]) -> bool {
+    let mode = |requirements: &[ActionRequirement]| {
+        requirements
+            .iter()
+            .find_map(|requirement| match requirement {
+                ActionRequirement::InMode(mode) => Some(*mode),
+                _ => None,
+            })
+    };
+
+    match (mode(first), mode(second)) {
+        (Some(first_mode), Some(second_mode)) => first_mode == second_mode,
+        _ => true,
+    }
+}
+
+/// Camera keys can only be used in Ctrl or Alt chords, those keys are hidden from the camera in `camera.rs`.
+fn moves_camera(binding: &ActionBinding) -> bool {
+    let is_camera_key = match binding.input {
+        InputButton::Key(key) => CAMERA_MOVEMENT_KEYS.contains(&key),
+        _ => false,
+    };
+
+    is_camera_key
+        && !binding
+            .modifiers
+            .iter()
+            .any(|modifier| matches!(modifier, Modifier::Control | Modifier::Alt))
+}
+
+/// Two different actions on the same chord conflict when both can trigger at the same time.
+fn bindings_conflict(first: &ActionBinding, second: &ActionBinding) -> bool {
+    first.input == second.input
+        && first.modifiers == second.modifiers
+        && first.action != second.action
+        && !is_held_action(first.action)
+        && !is_held_action(second.action)
+        && requirements_overlap(&first.requirements, &second.requirements)
+}
+
+fn validate_bindings(bindings: &[ActionBinding]) -> Result<(), KeybindingError> {
+    if let Some(binding) = bindings.iter().find(|binding| moves_camera(binding)) {
+        return Err(KeybindingError::CameraKey {
+            input: chord_name(binding),
+            action: binding.action,
+        });
+    }
+
+    for (index, first) in bindings.iter().enumerate() {
+        for second in bindings.iter().skip(index + 1) {
+            if bindings_conflict(first, second) {
+                return Err(KeybindingError::Conflict {
+                    input: chord_name(first),
+                    first: first.action,
+                    second: second.action,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ron::ser::PrettyConfig;
+
+    use crate::player::editor_modes::EditorMode;
+
+    use super::*;
+
+    #[test]
+    fn default_bindings_survive_a_round_trip() {
+        let input_map = InputMap::default();
+
+        let serialized =
+            ron::ser::to_string_pretty(&to_config(&input_map), PrettyConfig::default()).unwrap();
+
+        assert_eq!(
+            parse_keybindings(&serialized).unwrap(),
+            (input_map, default_actions())
+        );
+    }
+
+    #[test]
+    fn bindings_are_read_by_name() {
+        let file = r#"(
+            bindings: [
+                (input: "Comma", action: SwitchEditorMode),
+                (input: "mouseright", action: UseTool, requirements: [CursorOffUi]),
+                (input: "GamepadSouth", action: PlaceBlock),
+            ],
+        )"#;
+
+        let (input_map, _) = parse_keybindings(file).unwrap();
+
+        assert!(input_map.is_bound(EditorAction::SwitchEditorMode, KeyCode::Comma));
+        assert!(input_map.is_bound(EditorAction::UseTool, MouseButton::Right));
+        assert!(input_map.is_bound(EditorAction::PlaceBlock, GamepadButtonType::South));
+    }
+
+    #[test]
+    fn actions_missing_from_the_file_get_their_default_binding() {
+        // Written before the file listed its known actions.
+        let file = r#"(
+            bindings: [
+                (input: "Comma", action: SwitchEditorMode),
+                (input: "MouseLeft", action: UseTool, requirements: [CursorOffUi]),
+            ],
+        )"#;
+
+        let (input_map, known_actions) = parse_keybindings(file).unwrap();
+        let (input_map, added_actions) = add_missing_default_bindings(input_map, &known_actions);
+
+        assert!(input_map.is_bound(EditorAction::ToggleConsole, KeyCode::Grave));
+        assert!(added_actions.contains(&EditorAction::ToggleConsole));
+
+        // The file binding is kept, the default on the same key is left out.
+        assert!(input_map.is_bound(EditorAction::SwitchEditorMode, KeyCode::Comma));
+        assert!(!input_map.is_bound(EditorAction::SwitchEditorMode, KeyCode::M));
+        assert!(!input_map.is_bound(EditorAction::ToggleSettings, KeyCode::Comma));
+        assert!(validate_bindings(input_map.bindings()).is_ok());
+    }
+
+    #[test]
+    fn complete_files_get_no_extra_bindings() {
+        let (input_map, added_actions) =
+            add_missing_default_bindings(InputMap::default(), &default_actions());
+
+        assert!(added_actions.is_empty());
+        assert_eq!(input_map, InputMap::default());
+    }
+
+    #[test]
+    fn removed_bindings_stay_removed() {
+        let file = r#"(
+            known_actions: [SaveScene, LoadScene],
+            bindings: [
+                (input: "O", action: LoadScene),
+            ],
+        )"#;
+
+        let (input_map, known_actions) = parse_keybindings(file).unwrap();
+        let (input_map, added_actions) = add_missing_default_bindings(input_map, &known_actions);
+
+        assert!(!input_map.is_bound(EditorAction::SaveScene, KeyCode::I));
+        assert!(!added_actions.contains(&EditorAction::SaveScene));
+
+        // Newer than the file, so it gets its default binding.
+        assert!(input_map.is_bound(EditorAction::ToggleConsole, KeyCode::Grave));
+    }
+
+    #[test]
+    fn unknown_key_names_are_reported() {
+        let file = r#"(bindings: [(input: "Shfit", action: Remove)])"#;
+
+        let error = parse_keybindings(file).unwrap_err();
+
+        assert!(matches!(&error, KeybindingError::UnknownInput(name) if name == "Shfit"));
+        assert!(error.to_string().contains("'Shfit'"));
+    }
+
+    #[test]
+    fn chords_are_read_with_their_modifiers() {
+        let (modifiers, input) = parse_chord("shift + Ctrl+Z").unwrap();
+
+        assert_eq!(modifiers, vec![Modifier::Control, Modifier::Shift]);
+        assert_eq!(input, InputButton::Key(KeyCode::Z));
+
+        let binding = ActionBinding::new(input, EditorAction::GrowBrush)
+            .with_modifier(Modifier::Shift)
+            .with_modifier(Modifier::Control);
+
+        assert_eq!(chord_name(&binding), "Ctrl+Shift+Z");
+        assert!(matches!(
+            parse_chord("Q+Z"),
+            Err(KeybindingError::UnknownInput(name)) if name == "Q"
+        ));
+    }
+
+    #[test]
+    fn camera_keys_need_ctrl_or_alt() {
+        let shift_w =
+            vec![ActionBinding::new(KeyCode::W, EditorAction::SaveScene)
+                .with_modifier(Modifier::Shift)];
+        let ctrl_w = vec![ActionBinding::new(KeyCode::W, EditorAction::SaveScene)
+            .with_modifier(Modifier::Control)];
+
+        assert!(matches!(
+            validate_bindings(&shift_w),
+            Err(KeybindingError::CameraKey { .. })
+        ));
+        assert!(validate_bindings(&ctrl_w).is_ok());
+    }
+
+    #[test]
+    fn chord_and_plain_key_dont_conflict() {
+        let bindings = vec![
+            ActionBinding::new(KeyCode::O, EditorAction::LoadScene),
+            ActionBinding::new(KeyCode::O, EditorAction::TogglePicker)
+                .with_modifier(Modifier::Control),
+        ];
+
+        assert!(validate_bindings(&bindings).is_ok());
+    }
+
+    #[test]
+    fn same_key_for_two_actions_is_a_conflict() {
+        let bindings = vec![
+            ActionBinding::new(KeyCode::M, EditorAction::SwitchEditorMode),
+            ActionBinding::new(KeyCode::M, EditorAction::SaveScene),
+        ];
+
+        assert!(matches!(
+            validate_bindings(&bindings),
+            Err(KeybindingError::Conflict { .. })
+        ));
+    }
+
+    #[test]
+    fn keys_can_be_shared_between_modes_and_by_held_actions() {
+        let bindings = vec![
+            ActionBinding::new(KeyCode::B, EditorAction::SwitchBuildTool)
+                .with_requirement(ActionRequirement::InMode(EditorMode::Build)),
+            ActionBinding::new(KeyCode::B, EditorAction::ClearSelection)
+                .with_requirement(ActionRequirement::InMode(EditorMode::Select)),
+            ActionBinding::new(Modifier::Shift, EditorAction::Remove),
+            ActionBinding::new(Modifier::Shift, EditorAction::LooseReplace),
+        ];
+
+        assert!(validate_bindings(&bindings).is_ok());
+    }
+}
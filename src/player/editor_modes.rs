@@ -1,35 +1,50 @@
+pub mod brush;
 pub mod build;
+mod eyedropper;
+pub mod paint;
+pub mod replace_color;
 pub mod select;
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use self::{build::BuildModePlugin, select::SelectModePlugin};
+use self::{
+    brush::BrushPlugin, build::BuildModePlugin, eyedropper::EyedropperPlugin,
+    paint::PaintModePlugin, replace_color::ReplaceColorPlugin, select::SelectModePlugin,
+};
+use super::editor_actions::{action_triggered, EditorAction, OnEditorAction};
 
 pub struct EditorModesPlugin;
 
 impl Plugin for EditorModesPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((BuildModePlugin, SelectModePlugin))
-            .add_state::<EditorMode>()
-            .add_systems(Update, switch_editor_mode);
+        app.add_plugins((
+            BuildModePlugin,
+            SelectModePlugin,
+            PaintModePlugin,
+            BrushPlugin,
+            EyedropperPlugin,
+            ReplaceColorPlugin,
+        ))
+        .add_state::<EditorMode>()
+        .add_systems(Update, switch_editor_mode);
     }
 }
 
-const MODE_SWITCH_KEY: KeyCode = KeyCode::M;
-
-#[derive(Debug, PartialEq, Clone, Copy, States, Eq, Default, Hash)]
+#[derive(Debug, PartialEq, Clone, Copy, States, Eq, Default, Hash, Serialize, Deserialize)]
 pub enum EditorMode {
     #[default]
     Build,
     Select,
+    Paint,
 }
 
 fn switch_editor_mode(
-    input: Res<Input<KeyCode>>,
+    mut on_action: EventReader<OnEditorAction>,
     editor_mode: Res<State<EditorMode>>,
     mut next_editor_mode: ResMut<NextState<EditorMode>>,
 ) {
-    if input.just_pressed(MODE_SWITCH_KEY) {
+    if action_triggered(&mut on_action, EditorAction::SwitchEditorMode) {
         next_editor_mode.set(get_next_editor_mode(*editor_mode.get()));
         println!(
             "Switched to: {:?}",
@@ -41,6 +56,7 @@ fn switch_editor_mode(
 fn get_next_editor_mode(mode: EditorMode) -> EditorMode {
     match mode {
         EditorMode::Build => EditorMode::Select,
-        EditorMode::Select => EditorMode::Build,
+        EditorMode::Select => EditorMode::Paint,
+        EditorMode::Paint => EditorMode::Build,
     }
 }
@@ -0,0 +1,258 @@
+use bevy::prelude::*;
+
+use crate::{
+    newtypes::coordinate::Coordinate,
+    player::{
+        editor_actions::{EditorAction, OnEditorAction},
+        mouse_interaction::{
+            mouse_events::{OnMouseDrag, OnMousePressed},
+            mouse_target::MouseTarget,
+        },
+    },
+    world::WorldSettings,
+};
+
+use super::select::get_coordinates_between;
+
+pub struct BrushPlugin;
+
+impl Plugin for BrushPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Brush>()
+            .add_systems(Update, change_brush_on_actions);
+    }
+}
+
+const MAX_BRUSH_RADIUS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BrushShape {
+    #[default]
+    Cube,
+    Sphere,
+}
+
+/// What a brush stroke does to the blocks it touches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrushAction {
+    Add,
+    Remove,
+    Paint,
+}
+
+impl BrushAction {
+    /// Adding happens in front of the targeted face, the other actions change the targeted block itself,
+    /// so they have no center on the construction grid.
+    pub fn get_center(&self, target: MouseTarget) -> Option<Coordinate> {
+        match self {
+            BrushAction::Add => Some(target.out_coord),
+            BrushAction::Remove | BrushAction::Paint => target.hit_block(),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Brush {
+    pub shape: BrushShape,
+    /// A radius of 0 only touches the center block.
+    pub radius: usize,
+}
+
+impl Brush {
+    pub fn new(shape: BrushShape, radius: usize) -> Self {
+        Self { shape, radius }
+    }
+
+    pub fn grow(&mut self) {
+        self.radius = (self.radius + 1).min(MAX_BRUSH_RADIUS);
+    }
+
+    pub fn shrink(&mut self) {
+        self.radius = self.radius.saturating_sub(1);
+    }
+
+    pub fn toggle_shape(&mut self) {
+        self.shape = match self.shape {
+            BrushShape::Cube => BrushShape::Sphere,
+            BrushShape::Sphere => BrushShape::Cube,
+        };
+    }
+
+    /// Returns every coordinate this brush touches when applied at the given center.
+    pub fn get_coordinates(&self, center: Coordinate) -> Vec<Coordinate> {
+        let start = Coordinate::new(
+            center.x.saturating_sub(self.radius),
+            center.y.saturating_sub(self.radius),
+            center.z.saturating_sub(self.radius),
+        );
+        let end = Coordinate::new(
+            center.x + self.radius,
+            center.y + self.radius,
+            center.z + self.radius,
+        );
+
+        let coordinates = get_coordinates_between(start, end);
+
+        match self.shape {
+            BrushShape::Cube => coordinates,
+            BrushShape::Sphere => coordinates
+                .into_iter()
+                .filter(|coord| self.is_inside_sphere(center, *coord))
+                .collect(),
+        }
+    }
+
+    fn is_inside_sphere(&self, center: Coordinate, coord: Coordinate) -> bool {
+        let distance_squared = [
+            (center.x, coord.x),
+            (center.y, coord.y),
+            (center.z, coord.z),
+        ]
+        .iter()
+        .map(|(a, b)| a.abs_diff(*b).pow(2))
+        .sum::<usize>() as f32;
+
+        let radius = self.radius as f32 + 0.5;
+
+        distance_squared <= radius * radius
+    }
+}
+
+/// Tracks where a brush stroke was last applied, so holding the mouse still during a drag doesn't apply it every frame.
+#[derive(Default)]
+pub struct BrushStroke {
+    last_center: Option<Coordinate>,
+}
+
+impl BrushStroke {
+    /// Returns the centers to apply the brush at this frame, from presses and ongoing drags of the tool buttons.
+    pub fn get_centers(
+        &mut self,
+        action: BrushAction,
+        is_tool_button: impl Fn(MouseButton) -> bool,
+        on_mouse_pressed: &mut EventReader<OnMousePressed>,
+        on_mouse_drag: &mut EventReader<OnMouseDrag>,
+    ) -> Vec<Coordinate> {
+        let mut centers = Vec::new();
+
+        for mouse_pressed in on_mouse_pressed
+            .iter()
+            .filter(|mouse_pressed| is_tool_button(mouse_pressed.button))
+        {
+            if let Some(center) = mouse_pressed
+                .target
+                .and_then(|target| action.get_center(target))
+            {
+                centers.push(center);
+            }
+
+            self.last_center = None;
+        }
+
+        for mouse_drag in on_mouse_drag
+            .iter()
+            .filter(|mouse_drag| is_tool_button(mouse_drag.button))
+        {
+            if let Some(center) = mouse_drag.end.and_then(|target| action.get_center(target)) {
+                if self.last_center != Some(center) {
+                    centers.push(center);
+                    self.last_center = Some(center);
+                }
+            }
+
+            if mouse_drag.drag_ended() {
+                self.last_center = None;
+            }
+        }
+
+        centers
+    }
+}
+
+fn change_brush_on_actions(mut on_action: EventReader<OnEditorAction>, mut brush: ResMut<Brush>) {
+    let previous_brush = *brush;
+
+    for event in on_action.iter() {
+        match event.action {
+            EditorAction::SwitchBrushShape => brush.toggle_shape(),
+            EditorAction::GrowBrush => brush.grow(),
+            EditorAction::ShrinkBrush => brush.shrink(),
+            _ => {}
+        }
+    }
+
+    if *brush != previous_brush {
+        println!("Brush: {:?}", *brush);
+    }
+}
+
+// Gizmos
+
+pub fn draw_brush_preview(
+    gizmos: &mut Gizmos,
+    world_settings: &WorldSettings,
+    coordinates: Vec<Coordinate>,
+    color: Color,
+) {
+    for coord in coordinates.into_iter() {
+        let position = world_settings.coordinate_to_position(coord);
+
+        gizmos.cuboid(Transform::from_translation(position), color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brush_of_radius_zero_touches_one_block() {
+        let brush = Brush::new(BrushShape::Sphere, 0);
+        let center = Coordinate::new(3, 3, 3);
+
+        assert_eq!(brush.get_coordinates(center), vec![center]);
+    }
+
+    #[test]
+    fn cube_brush_fills_whole_cube() {
+        let brush = Brush::new(BrushShape::Cube, 1);
+
+        let coords = brush.get_coordinates(Coordinate::new(3, 3, 3));
+
+        assert_eq!(coords.len(), 27);
+        assert!(coords.contains(&Coordinate::new(2, 2, 2)));
+    }
+
+    #[test]
+    fn sphere_brush_skips_corners() {
+        let brush = Brush::new(BrushShape::Sphere, 1);
+
+        let coords = brush.get_coordinates(Coordinate::new(3, 3, 3));
+
+        assert_eq!(coords.len(), 19);
+        assert!(coords.contains(&Coordinate::new(2, 2, 3)));
+        assert!(!coords.contains(&Coordinate::new(2, 2, 2)));
+    }
+
+    #[test]
+    fn brush_stops_at_zero_coordinates() {
+        let brush = Brush::new(BrushShape::Cube, 2);
+
+        let coords = brush.get_coordinates(Coordinate::new(0, 0, 0));
+
+        assert_eq!(coords.len(), 27);
+    }
+
+    #[test]
+    fn brush_radius_stays_in_range() {
+        let mut brush = Brush::new(BrushShape::Cube, 0);
+
+        brush.shrink();
+        assert_eq!(brush.radius, 0);
+
+        for _ in 0..20 {
+            brush.grow();
+        }
+        assert_eq!(brush.radius, MAX_BRUSH_RADIUS);
+    }
+}
@@ -1,38 +1,97 @@
+mod brush_tool;
+mod drag_constraint;
+mod face_tool;
+pub mod shapes;
+
 use bevy::prelude::*;
 
 use crate::{
     game_systems::color_library::ColorLibrary,
     newtypes::coordinate::Coordinate,
-    player::mouse_interaction::{
-        mouse_events::{OnMouseDrag, OnMousePressed},
-        mouse_target::MouseTarget,
+    player::{
+        editor_actions::{action_triggered, ActionState, EditorAction, OnEditorAction},
+        editor_settings::EditorSettings,
+        mouse_interaction::{
+            mouse_events::{OnMouseDrag, OnMousePressed},
+            mouse_target::MouseTarget,
+            MouseInteraction,
+        },
+    },
+    world::{
+        block::{
+            block_shape::{BlockOrientation, BlockShape},
+            Block,
+        },
+        WorldSettings,
     },
-    world::{block::Block, WorldSettings},
 };
 
-use super::{select::get_coordinates_between, EditorMode};
+use self::{
+    brush_tool::BuildBrushPlugin,
+    drag_constraint::{update_build_drag, BuildDrag, DragConstraintPlugin},
+    face_tool::FaceToolPlugin,
+    shapes::{BuildShape, BuildShapesPlugin},
+};
+use super::EditorMode;
 
 pub struct BuildModePlugin;
 
 impl Plugin for BuildModePlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<OnPlaceBlockRequest>()
-            .add_event::<OnRemoveBlockRequest>()
-            .add_systems(
-                Update,
-                (
-                    handle_mouse_press,
-                    handle_mouse_drag,
-                    handle_remove_input,
-                    draw_block_placement_preview,
-                )
-                    .run_if(in_state(EditorMode::Build)),
-            );
+        app.add_plugins((
+            BuildShapesPlugin,
+            BuildBrushPlugin,
+            FaceToolPlugin,
+            DragConstraintPlugin,
+        ))
+        .add_event::<OnPlaceBlockRequest>()
+        .add_event::<OnRemoveBlockRequest>()
+        .init_resource::<BuildTool>()
+        .init_resource::<SelectedBlockShape>()
+        .add_systems(
+            Update,
+            (
+                handle_mouse_press,
+                handle_mouse_drag,
+                handle_remove_input,
+                draw_block_placement_preview,
+            )
+                .after(update_build_drag)
+                .run_if(in_state(EditorMode::Build).and_then(resource_equals(BuildTool::Shape))),
+        )
+        .add_systems(
+            Update,
+            (switch_build_tool, switch_block_shape, handle_target_actions)
+                .run_if(in_state(EditorMode::Build)),
+        );
     }
 }
 
-const BUILD_BUTTON: MouseButton = MouseButton::Left;
-const REMOVE_KEY: KeyCode = KeyCode::ShiftLeft;
+/// Decides what a click or drag does in build mode.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BuildTool {
+    /// Build a single block on click, or the selected `BuildShape` on drag.
+    #[default]
+    Shape,
+    /// Add or remove blocks with the `Brush` while dragging.
+    Brush,
+    /// Extrude or carve the clicked face by dragging along its normal.
+    Face,
+}
+
+impl BuildTool {
+    fn next(&self) -> Self {
+        match self {
+            BuildTool::Shape => BuildTool::Brush,
+            BuildTool::Brush => BuildTool::Face,
+            BuildTool::Face => BuildTool::Shape,
+        }
+    }
+}
+
+/// The shape of blocks placed with the shape tool, their orientation follows the placement.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SelectedBlockShape(pub BlockShape);
 
 #[derive(Event)]
 pub struct OnPlaceBlockRequest {
@@ -57,68 +116,160 @@ impl OnRemoveBlockRequest {
     }
 }
 
+fn switch_build_tool(
+    mut on_action: EventReader<OnEditorAction>,
+    mut build_tool: ResMut<BuildTool>,
+) {
+    if action_triggered(&mut on_action, EditorAction::SwitchBuildTool) {
+        *build_tool = build_tool.next();
+        println!("Build tool: {:?}", *build_tool);
+    }
+}
+
+fn switch_block_shape(
+    mut on_action: EventReader<OnEditorAction>,
+    mut selected_block_shape: ResMut<SelectedBlockShape>,
+) {
+    if action_triggered(&mut on_action, EditorAction::SwitchBlockShape) {
+        selected_block_shape.0 = selected_block_shape.0.next();
+        println!("Block shape: {:?}", selected_block_shape.0);
+    }
+}
+
 fn handle_mouse_press(
     mut on_mouse_pressed: EventReader<OnMousePressed>,
-    key_input: Res<Input<KeyCode>>,
+    action_state: Res<ActionState>,
     color_library: Res<ColorLibrary>,
+    selected_block_shape: Res<SelectedBlockShape>,
+    mouse_interaction: Res<MouseInteraction>,
     mut place_event: EventWriter<OnPlaceBlockRequest>,
 ) {
     for mouse_pressed in on_mouse_pressed.iter() {
-        if mouse_pressed.button != BUILD_BUTTON || key_input.pressed(REMOVE_KEY) {
+        if !action_state.button_triggered(EditorAction::UseTool, mouse_pressed.button)
+            || action_state.is_held(EditorAction::Remove)
+        {
             continue;
         }
 
         if let Some(target) = mouse_pressed.target {
-            send_place_block_request(&mut place_event, &color_library, target.out_coord);
+            let block_shape = placed_block_shape(&selected_block_shape, &mouse_interaction, target);
+
+            send_place_block_request(
+                &mut place_event,
+                &color_library,
+                block_shape,
+                target.out_coord,
+            );
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn handle_mouse_drag(
     mut on_mouse_drag: EventReader<OnMouseDrag>,
-    key_input: Res<Input<KeyCode>>,
+    action_state: Res<ActionState>,
     color_library: Res<ColorLibrary>,
+    build_shape: Res<BuildShape>,
+    build_drag: Res<BuildDrag>,
+    selected_block_shape: Res<SelectedBlockShape>,
+    mouse_interaction: Res<MouseInteraction>,
     mut place_event: EventWriter<OnPlaceBlockRequest>,
 ) {
     for mouse_drag in on_mouse_drag.iter() {
-        if mouse_drag.button != BUILD_BUTTON
+        if !action_state.button_triggered(EditorAction::UseTool, mouse_drag.button)
             || !mouse_drag.drag_ended()
-            || key_input.pressed(REMOVE_KEY)
+            || action_state.is_held(EditorAction::Remove)
         {
             continue;
         }
 
-        if let (Some(start), Some(end)) = (
-            mouse_drag.start.map(|target| target.out_coord),
-            mouse_drag.end.map(|target| target.out_coord),
-        ) {
-            for coord in get_coordinates_between(start, end).into_iter() {
-                send_place_block_request(&mut place_event, &color_library, coord);
+        if let (Some(start), Some(end)) = (mouse_drag.start, build_drag.end()) {
+            // Every block of the shape gets the orientation of the face the drag started on.
+            let block_shape = placed_block_shape(&selected_block_shape, &mouse_interaction, start);
+
+            for coord in build_shape
+                .get_coordinates(start.out_coord, end)
+                .into_iter()
+            {
+                send_place_block_request(&mut place_event, &color_library, block_shape, coord);
             }
         }
     }
 }
 
+/// Places or removes a block at the target, for inputs like gamepad triggers that don't make mouse gestures.
+fn handle_target_actions(
+    mut on_action: EventReader<OnEditorAction>,
+    color_library: Res<ColorLibrary>,
+    selected_block_shape: Res<SelectedBlockShape>,
+    mouse_interaction: Res<MouseInteraction>,
+    mut place_event: EventWriter<OnPlaceBlockRequest>,
+    mut remove_event: EventWriter<OnRemoveBlockRequest>,
+) {
+    let Some(target) = mouse_interaction.target() else {
+        on_action.clear();
+        return;
+    };
+
+    for event in on_action.iter() {
+        match event.action {
+            EditorAction::PlaceBlock => {
+                let block_shape =
+                    placed_block_shape(&selected_block_shape, &mouse_interaction, target);
+
+                send_place_block_request(
+                    &mut place_event,
+                    &color_library,
+                    block_shape,
+                    target.out_coord,
+                );
+            }
+            EditorAction::RemoveBlock => send_remove_block_request(&mut remove_event, target),
+            _ => {}
+        }
+    }
+}
+
+/// Orients the selected block shape to the clicked face and the direction the camera looks through the cursor.
+fn placed_block_shape(
+    selected_block_shape: &SelectedBlockShape,
+    mouse_interaction: &MouseInteraction,
+    target: MouseTarget,
+) -> (BlockShape, BlockOrientation) {
+    let view_direction = mouse_interaction
+        .ray_through_cursor()
+        .map(|ray| ray.direction)
+        .unwrap_or(Vec3::NEG_Z);
+
+    (
+        selected_block_shape.0,
+        BlockOrientation::from_placement(target.normal, view_direction),
+    )
+}
+
 fn send_place_block_request(
     place_event: &mut EventWriter<OnPlaceBlockRequest>,
     color_library: &ColorLibrary,
+    (shape, orientation): (BlockShape, BlockOrientation),
     coord: Coordinate,
 ) {
     place_event.send(OnPlaceBlockRequest::new(
-        color_library
-            .selected_color()
-            .map(|color| Block::new(color)),
+        color_library.selected_color().map(|_| {
+            Block::new(color_library.selected_index()).with_shape(shape, orientation)
+        }),
         coord,
     ));
 }
 
 fn handle_remove_input(
     mut on_mouse_pressed: EventReader<OnMousePressed>,
-    key_input: Res<Input<KeyCode>>,
+    action_state: Res<ActionState>,
     mut remove_event: EventWriter<OnRemoveBlockRequest>,
 ) {
     for mouse_pressed in on_mouse_pressed.iter() {
-        if mouse_pressed.button != BUILD_BUTTON || !key_input.pressed(REMOVE_KEY) {
+        if !action_state.button_triggered(EditorAction::UseTool, mouse_pressed.button)
+            || !action_state.is_held(EditorAction::Remove)
+        {
             continue;
         }
 
@@ -132,28 +283,38 @@ fn send_remove_block_request(
     remove_event: &mut EventWriter<OnRemoveBlockRequest>,
     target: MouseTarget,
 ) {
-    remove_event.send(OnRemoveBlockRequest::new(Coordinate::from(target.in_coord)));
+    if let Some(coord) = target.hit_block() {
+        remove_event.send(OnRemoveBlockRequest::new(coord));
+    }
 }
 
 fn draw_block_placement_preview(
     mut on_mouse_drag: EventReader<OnMouseDrag>,
     mut gizmos: Gizmos,
-    key_input: Res<Input<KeyCode>>,
+    action_state: Res<ActionState>,
     world_settings: Res<WorldSettings>,
+    editor_settings: Res<EditorSettings>,
+    build_shape: Res<BuildShape>,
+    build_drag: Res<BuildDrag>,
 ) {
     for mouse_drag in on_mouse_drag.iter() {
-        if mouse_drag.button != BUILD_BUTTON || key_input.pressed(REMOVE_KEY) {
+        if !action_state.button_triggered(EditorAction::UseTool, mouse_drag.button)
+            || action_state.is_held(EditorAction::Remove)
+        {
             continue;
         }
 
         if let (Some(start), Some(end)) = (
             mouse_drag.start.map(|target| target.out_coord),
-            mouse_drag.end.map(|target| target.out_coord),
+            build_drag.end(),
         ) {
-            for coord in get_coordinates_between(start, end).into_iter() {
+            for coord in build_shape.get_coordinates(start, end).into_iter() {
                 let in_position = world_settings.coordinate_to_position(coord);
 
-                gizmos.cuboid(Transform::from_translation(in_position), Color::CYAN);
+                gizmos.cuboid(
+                    Transform::from_translation(in_position),
+                    editor_settings.placement_color(),
+                );
             }
         }
     }
@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+
+use crate::{
+    game_systems::color_library::ColorLibrary,
+    player::{
+        editor_actions::{ActionState, EditorAction},
+        editor_modes::{
+            brush::{draw_brush_preview, Brush, BrushAction, BrushStroke},
+            EditorMode,
+        },
+        editor_settings::EditorSettings,
+        mouse_interaction::{
+            mouse_events::{OnMouseDrag, OnMousePressed},
+            MouseInteraction,
+        },
+    },
+    world::{
+        block::block_shape::{BlockOrientation, BlockShape},
+        chunk::Chunk,
+        WorldSettings,
+    },
+};
+
+use super::{send_place_block_request, BuildTool, OnPlaceBlockRequest, OnRemoveBlockRequest};
+
+pub struct BuildBrushPlugin;
+
+impl Plugin for BuildBrushPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (apply_brush, draw_brush_target_preview)
+                .run_if(in_state(EditorMode::Build).and_then(resource_equals(BuildTool::Brush))),
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn apply_brush(
+    mut on_mouse_pressed: EventReader<OnMousePressed>,
+    mut on_mouse_drag: EventReader<OnMouseDrag>,
+    mut brush_stroke: Local<BrushStroke>,
+    action_state: Res<ActionState>,
+    brush: Res<Brush>,
+    color_library: Res<ColorLibrary>,
+    chunks: Query<&Chunk>,
+    mut place_event: EventWriter<OnPlaceBlockRequest>,
+    mut remove_event: EventWriter<OnRemoveBlockRequest>,
+) {
+    let action = get_brush_action(&action_state);
+    let chunk = chunks.single();
+
+    let centers = brush_stroke.get_centers(
+        action,
+        |button| action_state.button_triggered(EditorAction::UseTool, button),
+        &mut on_mouse_pressed,
+        &mut on_mouse_drag,
+    );
+
+    for coord in centers
+        .into_iter()
+        .flat_map(|center| brush.get_coordinates(center))
+    {
+        match action {
+            BrushAction::Add if chunk.get_block(coord).is_none() => {
+                send_place_block_request(
+                    &mut place_event,
+                    &color_library,
+                    (BlockShape::Full, BlockOrientation::default()),
+                    coord,
+                );
+            }
+            BrushAction::Remove if chunk.get_block(coord).is_some() => {
+                remove_event.send(OnRemoveBlockRequest::new(coord));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn get_brush_action(action_state: &ActionState) -> BrushAction {
+    if action_state.is_held(EditorAction::Remove) {
+        BrushAction::Remove
+    } else {
+        BrushAction::Add
+    }
+}
+
+// Gizmos
+
+fn draw_brush_target_preview(
+    mut gizmos: Gizmos,
+    mouse_interaction: Res<MouseInteraction>,
+    action_state: Res<ActionState>,
+    brush: Res<Brush>,
+    world_settings: Res<WorldSettings>,
+    editor_settings: Res<EditorSettings>,
+) {
+    if let Some(target) = mouse_interaction.target() {
+        let action = get_brush_action(&action_state);
+        let Some(center) = action.get_center(target) else {
+            return;
+        };
+        let color = match action {
+            BrushAction::Remove => editor_settings.remove_color(),
+            _ => editor_settings.placement_color(),
+        };
+
+        draw_brush_preview(
+            &mut gizmos,
+            &world_settings,
+            brush.get_coordinates(center),
+            color,
+        );
+    }
+}
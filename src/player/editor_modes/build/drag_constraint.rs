@@ -0,0 +1,238 @@
+use bevy::prelude::*;
+
+use crate::{
+    newtypes::coordinate::Coordinate,
+    player::{
+        editor_actions::{ActionState, EditorAction, OnEditorAction},
+        editor_modes::EditorMode,
+        mouse_interaction::{
+            mouse_events::{EventPhase, OnMouseDrag},
+            mouse_target::MouseTarget,
+            MouseInteraction,
+        },
+    },
+    world::WorldSettings,
+};
+
+pub struct DragConstraintPlugin;
+
+impl Plugin for DragConstraintPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DragConstraint>()
+            .init_resource::<BuildDrag>()
+            .add_systems(
+                Update,
+                (change_drag_on_actions, update_build_drag)
+                    .chain()
+                    .run_if(in_state(EditorMode::Build)),
+            );
+    }
+}
+
+/// Decides where a build drag ends.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DragConstraint {
+    /// The end follows the plane of the face the drag started on, so floors and walls stay flat.
+    #[default]
+    Plane,
+    /// The end is the cell in front of whatever the cursor points at.
+    Free,
+}
+
+impl DragConstraint {
+    fn next(&self) -> Self {
+        match self {
+            DragConstraint::Plane => DragConstraint::Free,
+            DragConstraint::Free => DragConstraint::Plane,
+        }
+    }
+}
+
+/// The end of the current build drag, after the constraints are applied.
+#[derive(Resource, Debug, Default)]
+pub struct BuildDrag {
+    /// Steps along the normal of the start face, changed with actions during the drag.
+    height: i32,
+    end: Option<Coordinate>,
+}
+
+impl BuildDrag {
+    pub fn end(&self) -> Option<Coordinate> {
+        self.end
+    }
+}
+
+fn change_drag_on_actions(
+    mut on_action: EventReader<OnEditorAction>,
+    mut drag_constraint: ResMut<DragConstraint>,
+    mut build_drag: ResMut<BuildDrag>,
+) {
+    for event in on_action.iter() {
+        match event.action {
+            EditorAction::SwitchDragConstraint => {
+                *drag_constraint = drag_constraint.next();
+                println!("Drag constraint: {:?}", *drag_constraint);
+            }
+            EditorAction::RaiseDrag => build_drag.height += 1,
+            EditorAction::LowerDrag => build_drag.height -= 1,
+            _ => {}
+        }
+    }
+}
+
+pub fn update_build_drag(
+    mut on_mouse_drag: EventReader<OnMouseDrag>,
+    action_state: Res<ActionState>,
+    drag_constraint: Res<DragConstraint>,
+    mouse_interaction: Res<MouseInteraction>,
+    world_settings: Res<WorldSettings>,
+    mut build_drag: ResMut<BuildDrag>,
+) {
+    for mouse_drag in on_mouse_drag.iter().filter(|mouse_drag| {
+        action_state.button_triggered(EditorAction::UseTool, mouse_drag.button)
+    }) {
+        if mouse_drag.phase == EventPhase::Started {
+            build_drag.height = 0;
+        }
+
+        let Some(start) = mouse_drag.start else {
+            build_drag.end = None;
+            continue;
+        };
+
+        let end = match *drag_constraint {
+            DragConstraint::Plane => mouse_interaction
+                .ray_through_cursor()
+                .and_then(|ray| {
+                    start.project_on_face_plane(
+                        ray,
+                        mouse_interaction.max_interaction_distance(),
+                        world_settings.block_scale(),
+                    )
+                })
+                .map(|end| end.out_coord)
+                .or(mouse_drag.end.map(|end| end.out_coord)),
+            DragConstraint::Free => mouse_drag.end.map(|end| end.out_coord),
+        };
+
+        build_drag.end = end.map(|end| {
+            constrain_end(
+                start,
+                end,
+                action_state.is_held(EditorAction::LockDragAxis),
+                build_drag.height,
+                world_settings.chunk_size(),
+            )
+        });
+    }
+}
+
+/// Locks the end to the longest axis of the drag if asked, and moves it the height steps along the start normal.
+///
+/// The end is clamped to the chunk on every axis.
+fn constrain_end(
+    start: MouseTarget,
+    end: Coordinate,
+    lock_axis: bool,
+    height: i32,
+    chunk_size: usize,
+) -> Coordinate {
+    let mut offset = coordinate_to_ivec(end) - coordinate_to_ivec(start.out_coord);
+
+    if lock_axis {
+        offset = longest_axis(offset);
+    }
+
+    let last = chunk_size.saturating_sub(1) as i32;
+    let end = (coordinate_to_ivec(start.out_coord) + offset + start.normal.to_offset() * height)
+        .clamp(IVec3::ZERO, IVec3::splat(last));
+
+    Coordinate::new(end.x as usize, end.y as usize, end.z as usize)
+}
+
+fn longest_axis(offset: IVec3) -> IVec3 {
+    let length = offset.abs();
+
+    if length.x >= length.y && length.x >= length.z {
+        IVec3::new(offset.x, 0, 0)
+    } else if length.y >= length.z {
+        IVec3::new(0, offset.y, 0)
+    } else {
+        IVec3::new(0, 0, offset.z)
+    }
+}
+
+fn coordinate_to_ivec(coord: Coordinate) -> IVec3 {
+    IVec3::new(coord.x as i32, coord.y as i32, coord.z as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::newtypes::direction::Direction;
+
+    use super::*;
+
+    /// The top face of the block at (2, 0, 2).
+    fn floor_start() -> MouseTarget {
+        MouseTarget {
+            point: Vec3::new(2.0, 0.5, 2.0),
+            normal: Direction::Y,
+            in_coord: Coordinate::new(2, 0, 2),
+            out_coord: Coordinate::new(2, 1, 2),
+            on_grid: false,
+        }
+    }
+
+    #[test]
+    fn end_stays_on_the_start_face_plane() {
+        let ray = Ray {
+            origin: Vec3::new(6.0, 10.0, 4.0),
+            direction: Vec3::NEG_Y,
+        };
+
+        assert_eq!(
+            floor_start()
+                .project_on_face_plane(ray, 20.0, 1.0)
+                .map(|end| end.out_coord),
+            Some(Coordinate::new(6, 1, 4))
+        );
+    }
+
+    #[test]
+    fn ray_almost_parallel_to_the_start_face_has_no_end() {
+        let ray = Ray {
+            origin: Vec3::new(2.0, 1.0, 2.0),
+            direction: Vec3::new(1.0, -0.001, 0.0).normalize(),
+        };
+
+        assert_eq!(floor_start().project_on_face_plane(ray, 20.0, 1.0), None);
+    }
+
+    #[test]
+    fn end_is_kept_inside_the_chunk() {
+        let end = constrain_end(floor_start(), Coordinate::new(40, 1, 3), false, 20, 16);
+
+        assert_eq!(end, Coordinate::new(15, 15, 3));
+    }
+
+    #[test]
+    fn locked_axis_keeps_the_longest_direction() {
+        let end = constrain_end(floor_start(), Coordinate::new(7, 1, 4), true, 0, 16);
+
+        assert_eq!(end, Coordinate::new(7, 1, 2));
+    }
+
+    #[test]
+    fn height_moves_along_the_start_normal() {
+        let end = constrain_end(floor_start(), Coordinate::new(5, 1, 5), false, 3, 16);
+
+        assert_eq!(end, Coordinate::new(5, 4, 5));
+    }
+
+    #[test]
+    fn end_below_zero_is_clamped_to_the_chunk() {
+        let end = constrain_end(floor_start(), Coordinate::new(0, 1, 0), false, -2, 16);
+
+        assert_eq!(end, Coordinate::new(0, 0, 0));
+    }
+}
@@ -0,0 +1,280 @@
+use bevy::prelude::*;
+
+use crate::{
+    newtypes::coordinate::Coordinate,
+    player::{
+        editor_actions::{ActionState, EditorAction},
+        editor_modes::EditorMode,
+        editor_settings::EditorSettings,
+        mouse_interaction::{mouse_events::OnMouseDrag, MouseInteraction},
+    },
+    world::{chunk::Chunk, WorldSettings},
+};
+
+use super::{BuildTool, OnPlaceBlockRequest, OnRemoveBlockRequest};
+
+pub struct FaceToolPlugin;
+
+impl Plugin for FaceToolPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FaceDrag>().add_systems(
+            Update,
+            (handle_face_drag, draw_face_drag_preview)
+                .chain()
+                .run_if(in_state(EditorMode::Build).and_then(resource_equals(BuildTool::Face))),
+        );
+    }
+}
+
+/// The face region that is being extruded or carved, and how far the drag currently reaches.
+#[derive(Resource, Debug, Default)]
+struct FaceDrag {
+    face: Vec<Coordinate>,
+    normal: IVec3,
+    origin: Vec3,
+    distance: i32,
+}
+
+impl FaceDrag {
+    fn is_active(&self) -> bool {
+        !self.face.is_empty()
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_face_drag(
+    mut on_mouse_drag: EventReader<OnMouseDrag>,
+    mut face_drag: ResMut<FaceDrag>,
+    action_state: Res<ActionState>,
+    mouse_interaction: Res<MouseInteraction>,
+    world_settings: Res<WorldSettings>,
+    chunks: Query<&Chunk>,
+    mut place_event: EventWriter<OnPlaceBlockRequest>,
+    mut remove_event: EventWriter<OnRemoveBlockRequest>,
+) {
+    let chunk = chunks.single();
+
+    for mouse_drag in on_mouse_drag.iter().filter(|mouse_drag| {
+        action_state.button_triggered(EditorAction::UseTool, mouse_drag.button)
+    }) {
+        if !face_drag.is_active() {
+            if let Some(start) = mouse_drag.start {
+                let normal = start.normal.to_offset();
+
+                face_drag.face = find_connected_face(chunk, start.in_coord, normal);
+                face_drag.normal = normal;
+                face_drag.origin = start.point;
+            }
+        }
+
+        if let Some(ray) = mouse_interaction.ray_through_cursor() {
+            if let Some(distance) =
+                distance_along_normal(face_drag.origin, face_drag.normal.as_vec3(), ray)
+            {
+                face_drag.distance = (distance / world_settings.block_scale()).round() as i32;
+            }
+        }
+
+        if mouse_drag.drag_ended() {
+            apply_face_drag(&face_drag, chunk, &mut place_event, &mut remove_event);
+            face_drag.reset();
+        }
+    }
+}
+
+fn apply_face_drag(
+    face_drag: &FaceDrag,
+    chunk: &Chunk,
+    place_event: &mut EventWriter<OnPlaceBlockRequest>,
+    remove_event: &mut EventWriter<OnRemoveBlockRequest>,
+) {
+    for (source, coord) in
+        get_face_drag_coordinates(&face_drag.face, face_drag.normal, face_drag.distance)
+    {
+        if face_drag.distance > 0 {
+            place_event.send(OnPlaceBlockRequest::new(chunk.get_block(source), coord));
+        } else {
+            remove_event.send(OnRemoveBlockRequest::new(coord));
+        }
+    }
+}
+
+/// Finds all exposed block faces that are connected to the start block and face the same direction.
+fn find_connected_face(chunk: &Chunk, start: Coordinate, normal: IVec3) -> Vec<Coordinate> {
+    let mut face = Vec::new();
+    let mut to_visit = vec![start];
+
+    let neighbour_offsets: Vec<IVec3> = [IVec3::X, IVec3::Y, IVec3::Z]
+        .into_iter()
+        .filter(|axis| axis.dot(normal) == 0)
+        .flat_map(|axis| [axis, -axis])
+        .collect();
+
+    while let Some(coord) = to_visit.pop() {
+        if face.contains(&coord) || !is_exposed_face(chunk, coord, normal) {
+            continue;
+        }
+
+        face.push(coord);
+
+        to_visit.extend(
+            neighbour_offsets
+                .iter()
+                .filter_map(|offset| coord.offset(*offset)),
+        );
+    }
+
+    face
+}
+
+fn is_exposed_face(chunk: &Chunk, coord: Coordinate, normal: IVec3) -> bool {
+    let in_front = coord
+        .offset(normal)
+        .and_then(|in_front| chunk.get_block(in_front));
+
+    chunk.get_block(coord).is_some() && in_front.is_none()
+}
+
+/// Returns pairs of (face coordinate, coordinate to change).
+/// A positive distance extrudes outward from the face, a negative distance carves into it.
+fn get_face_drag_coordinates(
+    face: &[Coordinate],
+    normal: IVec3,
+    distance: i32,
+) -> Vec<(Coordinate, Coordinate)> {
+    let steps: Vec<i32> = if distance > 0 {
+        (1..=distance).collect()
+    } else {
+        (0..-distance).map(|step| -step).collect()
+    };
+
+    face.iter()
+        .flat_map(|source| {
+            steps
+                .iter()
+                .filter_map(move |step| source.offset(normal * *step).map(|coord| (*source, coord)))
+        })
+        .collect()
+}
+
+/// Returns how far along the normal line the point closest to the ray is, or None if they are parallel.
+fn distance_along_normal(origin: Vec3, normal: Vec3, ray: Ray) -> Option<f32> {
+    let offset = origin - ray.origin;
+    let alignment = normal.dot(ray.direction);
+    let denominator = 1.0 - alignment * alignment;
+
+    if denominator < 0.0001 {
+        return None;
+    }
+
+    Some((alignment * ray.direction.dot(offset) - normal.dot(offset)) / denominator)
+}
+
+// Gizmos
+
+fn draw_face_drag_preview(
+    face_drag: Res<FaceDrag>,
+    mut gizmos: Gizmos,
+    world_settings: Res<WorldSettings>,
+    editor_settings: Res<EditorSettings>,
+) {
+    let color = if face_drag.distance > 0 {
+        editor_settings.placement_color()
+    } else {
+        editor_settings.remove_color()
+    };
+
+    for (_, coord) in
+        get_face_drag_coordinates(&face_drag.face, face_drag.normal, face_drag.distance)
+    {
+        let position = world_settings.coordinate_to_position(coord);
+
+        gizmos.cuboid(Transform::from_translation(position), color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::block::Block;
+
+    use super::*;
+
+    #[test]
+    fn finds_connected_top_face_of_ground() {
+        let chunk = Chunk::flat_ground(2, 0, 4);
+
+        let face = find_connected_face(&chunk, Coordinate::new(1, 1, 1), IVec3::Y);
+
+        assert_eq!(face.len(), 16);
+        assert!(face.iter().all(|coord| coord.y == 1));
+    }
+
+    #[test]
+    fn face_stops_at_covered_blocks() {
+        let mut chunk = Chunk::flat_ground(1, 0, 4);
+        for z in 0..4 {
+            chunk.set_block(Coordinate::new(2, 1, z), Some(Block::new(1)));
+        }
+
+        let face = find_connected_face(&chunk, Coordinate::new(0, 0, 0), IVec3::Y);
+
+        assert_eq!(face.len(), 8);
+        assert!(!face.contains(&Coordinate::new(3, 0, 0)));
+    }
+
+    #[test]
+    fn extruding_moves_away_from_face() {
+        let face = vec![Coordinate::new(1, 1, 1)];
+
+        let coords = get_face_drag_coordinates(&face, IVec3::Y, 2);
+
+        assert_eq!(
+            coords,
+            vec![
+                (Coordinate::new(1, 1, 1), Coordinate::new(1, 2, 1)),
+                (Coordinate::new(1, 1, 1), Coordinate::new(1, 3, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn carving_starts_at_face() {
+        let face = vec![Coordinate::new(1, 3, 1)];
+
+        let coords: Vec<Coordinate> = get_face_drag_coordinates(&face, IVec3::Y, -2)
+            .into_iter()
+            .map(|(_, coord)| coord)
+            .collect();
+
+        assert_eq!(
+            coords,
+            vec![Coordinate::new(1, 3, 1), Coordinate::new(1, 2, 1)]
+        );
+    }
+
+    #[test]
+    fn can_measure_ray_distance_along_normal() {
+        let ray = Ray {
+            origin: Vec3::new(5.0, 3.0, 0.0),
+            direction: Vec3::NEG_X,
+        };
+
+        let distance = distance_along_normal(Vec3::ZERO, Vec3::Y, ray);
+
+        assert_eq!(distance, Some(3.0));
+    }
+
+    #[test]
+    fn parallel_ray_has_no_distance() {
+        let ray = Ray {
+            origin: Vec3::new(5.0, 3.0, 0.0),
+            direction: Vec3::Y,
+        };
+
+        assert_eq!(distance_along_normal(Vec3::ZERO, Vec3::Y, ray), None);
+    }
+}
@@ -0,0 +1,313 @@
+use bevy::prelude::*;
+
+use crate::{
+    newtypes::coordinate::Coordinate,
+    player::{
+        editor_actions::{action_triggered, EditorAction, OnEditorAction},
+        editor_modes::{select::get_coordinates_between, EditorMode},
+    },
+};
+
+pub struct BuildShapesPlugin;
+
+impl Plugin for BuildShapesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BuildShape>().add_systems(
+            Update,
+            switch_build_shape.run_if(in_state(EditorMode::Build)),
+        );
+    }
+}
+
+/// The shape that gets built between the start and end of a drag.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BuildShape {
+    #[default]
+    Box,
+    HollowBox,
+    Walls,
+    Line,
+    Sphere,
+    Cylinder,
+    Pyramid,
+}
+
+impl BuildShape {
+    pub fn next(&self) -> Self {
+        match self {
+            BuildShape::Box => BuildShape::HollowBox,
+            BuildShape::HollowBox => BuildShape::Walls,
+            BuildShape::Walls => BuildShape::Line,
+            BuildShape::Line => BuildShape::Sphere,
+            BuildShape::Sphere => BuildShape::Cylinder,
+            BuildShape::Cylinder => BuildShape::Pyramid,
+            BuildShape::Pyramid => BuildShape::Box,
+        }
+    }
+
+    /// Returns every coordinate of this shape, fitted between the start and end coordinate.
+    pub fn get_coordinates(&self, start: Coordinate, end: Coordinate) -> Vec<Coordinate> {
+        match self {
+            BuildShape::Box => get_coordinates_between(start, end),
+            BuildShape::HollowBox => get_hollow_box_coordinates(start, end),
+            BuildShape::Walls => get_wall_coordinates(start, end),
+            BuildShape::Line => get_line_coordinates(start, end),
+            BuildShape::Sphere => get_ellipsoid_coordinates(start, end),
+            BuildShape::Cylinder => get_cylinder_coordinates(start, end),
+            BuildShape::Pyramid => get_pyramid_coordinates(start, end),
+        }
+    }
+}
+
+fn switch_build_shape(
+    mut on_action: EventReader<OnEditorAction>,
+    mut build_shape: ResMut<BuildShape>,
+) {
+    if action_triggered(&mut on_action, EditorAction::SwitchBuildShape) {
+        *build_shape = build_shape.next();
+        println!("Build shape: {:?}", *build_shape);
+    }
+}
+
+// Shapes
+
+fn get_hollow_box_coordinates(start: Coordinate, end: Coordinate) -> Vec<Coordinate> {
+    let (min, max) = get_bounds(start, end);
+
+    get_coordinates_between(start, end)
+        .into_iter()
+        .filter(|coord| {
+            is_on_edge(coord.x, min.x, max.x)
+                || is_on_edge(coord.y, min.y, max.y)
+                || is_on_edge(coord.z, min.z, max.z)
+        })
+        .collect()
+}
+
+/// Like a hollow box, but without floor and ceiling.
+fn get_wall_coordinates(start: Coordinate, end: Coordinate) -> Vec<Coordinate> {
+    let (min, max) = get_bounds(start, end);
+
+    get_coordinates_between(start, end)
+        .into_iter()
+        .filter(|coord| is_on_edge(coord.x, min.x, max.x) || is_on_edge(coord.z, min.z, max.z))
+        .collect()
+}
+
+/// Rasterizes a line using 3D Bresenham, stepping along the axis with the largest distance.
+fn get_line_coordinates(start: Coordinate, end: Coordinate) -> Vec<Coordinate> {
+    let from = to_signed(start);
+    let to = to_signed(end);
+
+    let delta = [
+        (to[0] - from[0]).abs(),
+        (to[1] - from[1]).abs(),
+        (to[2] - from[2]).abs(),
+    ];
+    let step = [
+        (to[0] - from[0]).signum(),
+        (to[1] - from[1]).signum(),
+        (to[2] - from[2]).signum(),
+    ];
+
+    let main_axis = (0..3).max_by_key(|axis| delta[*axis]).unwrap_or(0);
+    let steps = delta[main_axis];
+
+    let mut current = from;
+    let mut errors = [0i64; 3];
+    let mut result = vec![start];
+
+    for _ in 0..steps {
+        current[main_axis] += step[main_axis];
+
+        for axis in (0..3).filter(|axis| *axis != main_axis) {
+            errors[axis] += 2 * delta[axis];
+
+            if errors[axis] > steps {
+                current[axis] += step[axis];
+                errors[axis] -= 2 * steps;
+            }
+        }
+
+        result.push(from_signed(current));
+    }
+
+    result
+}
+
+/// Fills the ellipsoid that fits inside the box between start and end.
+fn get_ellipsoid_coordinates(start: Coordinate, end: Coordinate) -> Vec<Coordinate> {
+    let (center, radius) = get_center_and_radius(start, end);
+
+    get_coordinates_between(start, end)
+        .into_iter()
+        .filter(|coord| {
+            let offset = (to_vector(*coord) - center) / radius;
+            offset.length_squared() <= 1.0
+        })
+        .collect()
+}
+
+/// Fills the upright cylinder that fits inside the box between start and end.
+fn get_cylinder_coordinates(start: Coordinate, end: Coordinate) -> Vec<Coordinate> {
+    let (center, radius) = get_center_and_radius(start, end);
+
+    get_coordinates_between(start, end)
+        .into_iter()
+        .filter(|coord| {
+            let offset = (to_vector(*coord) - center) / radius;
+            offset.x * offset.x + offset.z * offset.z <= 1.0
+        })
+        .collect()
+}
+
+/// Builds a stepped pyramid on the bottom face of the box, layers above the box are cut off.
+fn get_pyramid_coordinates(start: Coordinate, end: Coordinate) -> Vec<Coordinate> {
+    let (min, max) = get_bounds(start, end);
+    let mut result = Vec::new();
+
+    let mut layer = 0;
+
+    while min.x + layer * 2 <= max.x && min.z + layer * 2 <= max.z && min.y + layer <= max.y {
+        result.extend(get_coordinates_between(
+            Coordinate::new(min.x + layer, min.y + layer, min.z + layer),
+            Coordinate::new(max.x - layer, min.y + layer, max.z - layer),
+        ));
+
+        layer += 1;
+    }
+
+    result
+}
+
+// Utility
+
+fn get_bounds(start: Coordinate, end: Coordinate) -> (Coordinate, Coordinate) {
+    (
+        Coordinate::new(start.x.min(end.x), start.y.min(end.y), start.z.min(end.z)),
+        Coordinate::new(start.x.max(end.x), start.y.max(end.y), start.z.max(end.z)),
+    )
+}
+
+fn get_center_and_radius(start: Coordinate, end: Coordinate) -> (Vec3, Vec3) {
+    let (min, max) = get_bounds(start, end);
+    let min = to_vector(min);
+    let max = to_vector(max);
+
+    // Adding half a block to the radius makes the shape reach the outer blocks of the box.
+    ((min + max) / 2.0, (max - min) / 2.0 + 0.5)
+}
+
+fn is_on_edge(value: usize, min: usize, max: usize) -> bool {
+    value == min || value == max
+}
+
+fn to_vector(coord: Coordinate) -> Vec3 {
+    Vec3::new(coord.x as f32, coord.y as f32, coord.z as f32)
+}
+
+fn to_signed(coord: Coordinate) -> [i64; 3] {
+    [coord.x as i64, coord.y as i64, coord.z as i64]
+}
+
+fn from_signed(values: [i64; 3]) -> Coordinate {
+    Coordinate::new(values[0] as usize, values[1] as usize, values[2] as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_cycle_through_all_shapes() {
+        let mut shape = BuildShape::Box;
+
+        for _ in 0..7 {
+            shape = shape.next();
+        }
+
+        assert_eq!(shape, BuildShape::Box);
+    }
+
+    #[test]
+    fn line_contains_start_and_end() {
+        let start = Coordinate::new(1, 2, 3);
+        let end = Coordinate::new(7, 4, 0);
+
+        let coords = get_line_coordinates(start, end);
+
+        assert_eq!(coords.first().cloned(), Some(start));
+        assert_eq!(coords.last().cloned(), Some(end));
+        assert_eq!(coords.len(), 7);
+    }
+
+    #[test]
+    fn straight_line_has_no_gaps() {
+        let coords = get_line_coordinates(Coordinate::new(0, 5, 0), Coordinate::new(0, 0, 0));
+
+        assert_eq!(coords.len(), 6);
+        assert!(coords.contains(&Coordinate::new(0, 3, 0)));
+    }
+
+    #[test]
+    fn line_of_single_coordinate() {
+        let coord = Coordinate::new(2, 2, 2);
+
+        assert_eq!(get_line_coordinates(coord, coord), vec![coord]);
+    }
+
+    #[test]
+    fn hollow_box_has_no_inside() {
+        let coords = get_hollow_box_coordinates(Coordinate::new(0, 0, 0), Coordinate::new(2, 2, 2));
+
+        assert_eq!(coords.len(), 26);
+        assert!(!coords.contains(&Coordinate::new(1, 1, 1)));
+    }
+
+    #[test]
+    fn walls_have_no_floor_or_ceiling() {
+        let coords = get_wall_coordinates(Coordinate::new(0, 0, 0), Coordinate::new(2, 2, 2));
+
+        assert_eq!(coords.len(), 24);
+        assert!(!coords.contains(&Coordinate::new(1, 0, 1)));
+        assert!(!coords.contains(&Coordinate::new(1, 2, 1)));
+    }
+
+    #[test]
+    fn sphere_fits_inside_box() {
+        let coords = get_ellipsoid_coordinates(Coordinate::new(0, 0, 0), Coordinate::new(4, 4, 4));
+
+        assert!(coords.contains(&Coordinate::new(2, 2, 2)));
+        assert!(coords.contains(&Coordinate::new(0, 2, 2)));
+        assert!(coords.contains(&Coordinate::new(2, 4, 2)));
+        assert!(!coords.contains(&Coordinate::new(0, 0, 0)));
+        assert!(!coords.contains(&Coordinate::new(4, 4, 4)));
+    }
+
+    #[test]
+    fn cylinder_keeps_full_height() {
+        let coords = get_cylinder_coordinates(Coordinate::new(0, 0, 0), Coordinate::new(4, 3, 4));
+
+        assert!(coords.contains(&Coordinate::new(2, 0, 0)));
+        assert!(coords.contains(&Coordinate::new(2, 3, 4)));
+        assert!(!coords.contains(&Coordinate::new(0, 0, 0)));
+        assert!(!coords.contains(&Coordinate::new(4, 3, 4)));
+    }
+
+    #[test]
+    fn pyramid_steps_in_towards_top() {
+        let coords = get_pyramid_coordinates(Coordinate::new(1, 0, 1), Coordinate::new(5, 2, 5));
+
+        assert_eq!(coords.len(), 25 + 9 + 1);
+        assert!(coords.contains(&Coordinate::new(3, 2, 3)));
+        assert!(!coords.contains(&Coordinate::new(1, 1, 1)));
+    }
+
+    #[test]
+    fn pyramid_stays_inside_the_box_height() {
+        let coords = get_pyramid_coordinates(Coordinate::new(0, 3, 0), Coordinate::new(8, 3, 8));
+
+        assert_eq!(coords.len(), 81);
+        assert!(coords.iter().all(|coord| coord.y == 3));
+    }
+}
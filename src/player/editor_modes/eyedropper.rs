@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+
+use crate::{
+    game_systems::color_library::{ColorLibrary, OnColorClicked},
+    player::{
+        editor_actions::{ActionState, EditorAction},
+        mouse_interaction::mouse_events::OnMousePressed,
+    },
+    world::chunk::Chunk,
+};
+
+use super::EditorMode;
+
+pub struct EyedropperPlugin;
+
+impl Plugin for EyedropperPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            pick_color_on_click
+                .run_if(in_state(EditorMode::Build).or_else(in_state(EditorMode::Paint))),
+        );
+    }
+}
+
+fn pick_color_on_click(
+    mut on_mouse_pressed: EventReader<OnMousePressed>,
+    action_state: Res<ActionState>,
+    chunks: Query<&Chunk>,
+    mut color_library: ResMut<ColorLibrary>,
+    mut on_color_clicked: EventWriter<OnColorClicked>,
+) {
+    let chunk = chunks.single();
+
+    for mouse_pressed in on_mouse_pressed.iter().filter(|mouse_pressed| {
+        action_state.button_triggered(EditorAction::PickColor, mouse_pressed.button)
+    }) {
+        let picked_block = mouse_pressed
+            .target
+            .and_then(|target| target.hit_block())
+            .and_then(|coord| chunk.get_block(coord));
+
+        if let Some(index) = picked_block.map(|block| block.palette_index()) {
+            if color_library.color_at(index).is_some() {
+                color_library.select_index(index);
+                on_color_clicked.send(OnColorClicked::new(index));
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+
+use crate::{
+    game_systems::color_library::ColorLibrary,
+    newtypes::coordinate::Coordinate,
+    player::{
+        editor_actions::{ActionState, EditorAction},
+        mouse_interaction::{
+            mouse_events::{OnMouseDrag, OnMousePressed},
+            MouseInteraction,
+        },
+    },
+    world::{chunk::Chunk, WorldSettings},
+};
+
+use super::{
+    brush::{draw_brush_preview, Brush, BrushAction, BrushStroke},
+    EditorMode,
+};
+
+pub struct PaintModePlugin;
+
+impl Plugin for PaintModePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OnPaintBlockRequest>().add_systems(
+            Update,
+            (paint_with_brush, draw_paint_preview).run_if(in_state(EditorMode::Paint)),
+        );
+    }
+}
+
+#[derive(Event)]
+pub struct OnPaintBlockRequest {
+    pub palette_index: usize,
+    pub coord: Coordinate,
+}
+
+impl OnPaintBlockRequest {
+    fn new(palette_index: usize, coord: Coordinate) -> Self {
+        Self {
+            palette_index,
+            coord,
+        }
+    }
+}
+
+fn paint_with_brush(
+    mut on_mouse_pressed: EventReader<OnMousePressed>,
+    mut on_mouse_drag: EventReader<OnMouseDrag>,
+    mut brush_stroke: Local<BrushStroke>,
+    action_state: Res<ActionState>,
+    brush: Res<Brush>,
+    color_library: Res<ColorLibrary>,
+    mut paint_event: EventWriter<OnPaintBlockRequest>,
+) {
+    let centers = brush_stroke.get_centers(
+        BrushAction::Paint,
+        |button| action_state.button_triggered(EditorAction::UseTool, button),
+        &mut on_mouse_pressed,
+        &mut on_mouse_drag,
+    );
+
+    if color_library.selected_color().is_none() {
+        return;
+    }
+
+    let palette_index = color_library.selected_index();
+
+    for coord in centers
+        .into_iter()
+        .flat_map(|center| brush.get_coordinates(center))
+    {
+        paint_event.send(OnPaintBlockRequest::new(palette_index, coord));
+    }
+}
+
+// Gizmos
+
+fn draw_paint_preview(
+    mut gizmos: Gizmos,
+    mouse_interaction: Res<MouseInteraction>,
+    brush: Res<Brush>,
+    chunks: Query<&Chunk>,
+    world_settings: Res<WorldSettings>,
+) {
+    if let Some(center) = mouse_interaction
+        .target()
+        .and_then(|target| BrushAction::Paint.get_center(target))
+    {
+        let chunk = chunks.single();
+
+        let coordinates = brush
+            .get_coordinates(center)
+            .into_iter()
+            .filter(|coord| chunk.get_block(*coord).is_some())
+            .collect();
+
+        draw_brush_preview(&mut gizmos, &world_settings, coordinates, Color::ORANGE);
+    }
+}
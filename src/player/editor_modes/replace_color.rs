@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+
+use crate::{
+    game_systems::color_library::ColorLibrary,
+    newtypes::coordinate::Coordinate,
+    player::{
+        editor_actions::{action_triggered, ActionState, EditorAction, OnEditorAction},
+        mouse_interaction::MouseInteraction,
+    },
+    world::chunk::Chunk,
+};
+
+use super::{select::CurrentSelection, EditorMode};
+
+pub struct ReplaceColorPlugin;
+
+impl Plugin for ReplaceColorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OnReplaceColorRequest>()
+            .add_systems(Update, send_replace_request_on_action);
+    }
+}
+
+const LOOSE_REPLACE_TOLERANCE: f32 = 0.1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplaceScope {
+    Scene,
+    Selection(Vec<Coordinate>),
+}
+
+/// Request to give every block using the `from` palette entry the `to` entry,
+/// with a tolerance entries of a similar color are replaced too.
+#[derive(Event, Debug, Clone)]
+pub struct OnReplaceColorRequest {
+    pub from: usize,
+    pub to: usize,
+    pub tolerance: f32,
+    pub scope: ReplaceScope,
+}
+
+impl OnReplaceColorRequest {
+    pub fn new(from: usize, to: usize, tolerance: f32, scope: ReplaceScope) -> Self {
+        Self {
+            from,
+            to,
+            tolerance,
+            scope,
+        }
+    }
+}
+
+/// Replaces the color of the targeted block with the selected color,
+/// inside the current selection if there is one, otherwise in the whole scene.
+#[allow(clippy::too_many_arguments)]
+fn send_replace_request_on_action(
+    mut on_action: EventReader<OnEditorAction>,
+    action_state: Res<ActionState>,
+    mouse_interaction: Res<MouseInteraction>,
+    editor_mode: Res<State<EditorMode>>,
+    current_selection: Res<CurrentSelection>,
+    color_library: Res<ColorLibrary>,
+    chunks: Query<&Chunk>,
+    mut on_replace_request: EventWriter<OnReplaceColorRequest>,
+) {
+    if !action_triggered(&mut on_action, EditorAction::ReplaceColor) {
+        return;
+    }
+
+    let chunk = chunks.single();
+
+    let from = mouse_interaction
+        .target()
+        .and_then(|target| target.hit_block())
+        .and_then(|coord| chunk.get_block(coord))
+        .map(|block| block.palette_index());
+
+    if let (Some(from), Some(_)) = (from, color_library.selected_color()) {
+        let to = color_library.selected_index();
+
+        let tolerance = if action_state.is_held(EditorAction::LooseReplace) {
+            LOOSE_REPLACE_TOLERANCE
+        } else {
+            0.0
+        };
+
+        let scope = get_replace_scope(*editor_mode.get(), &current_selection);
+
+        on_replace_request.send(OnReplaceColorRequest::new(from, to, tolerance, scope));
+    }
+}
+
+pub fn get_replace_scope(
+    editor_mode: EditorMode,
+    current_selection: &CurrentSelection,
+) -> ReplaceScope {
+    if editor_mode == EditorMode::Select && !current_selection.is_empty() {
+        ReplaceScope::Selection(current_selection.coordinates())
+    } else {
+        ReplaceScope::Scene
+    }
+}
@@ -1,10 +1,13 @@
 use bevy::prelude::*;
 
 use crate::{
-    game_systems::color_library::OnColorClicked,
+    game_systems::color_library::{recent_colors::RecentColors, ColorLibrary, OnColorClicked},
     newtypes::coordinate::Coordinate,
-    player::mouse_interaction::mouse_events::{OnMouseDrag, OnMousePressed},
-    world::{block::Block, chunk::Chunk, WorldSettings},
+    player::{
+        editor_actions::{action_triggered, ActionState, EditorAction, OnEditorAction},
+        mouse_interaction::mouse_events::{OnMouseDrag, OnMousePressed},
+    },
+    world::{chunk::Chunk, WorldSettings},
 };
 
 use super::EditorMode;
@@ -23,6 +26,7 @@ pub struct SelectModePlugin;
 impl Plugin for SelectModePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<CurrentSelection>()
+            .add_event::<OnSelectAreaRequest>()
             .add_systems(
                 Update,
                 (
@@ -34,32 +38,55 @@ impl Plugin for SelectModePlugin {
                 )
                     .run_if(in_state(EditorMode::Select)),
             )
+            .add_systems(
+                Update,
+                handle_select_area_requests.after(handle_drag_selection),
+            )
             .add_systems(OnExit(EditorMode::Select), clear_current_selection);
     }
 }
 
-const CLEAR_SELECTION_KEY: KeyCode = KeyCode::Delete;
-
 #[derive(Resource, Debug, Default)]
 pub struct CurrentSelection {
     coordinates: Vec<Coordinate>,
 }
 
 impl CurrentSelection {
+    pub fn coordinates(&self) -> Vec<Coordinate> {
+        self.coordinates.clone()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.coordinates.is_empty()
+    }
+
     fn clear_selection(&mut self) {
         self.coordinates.clear();
     }
 }
 
+/// Replaces the selection with every coordinate in the box between start and end.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct OnSelectAreaRequest {
+    pub start: Coordinate,
+    pub end: Coordinate,
+}
+
+impl OnSelectAreaRequest {
+    pub fn new(start: Coordinate, end: Coordinate) -> Self {
+        Self { start, end }
+    }
+}
+
 fn handle_mouse_pressed(
     mut on_mouse_pressed: EventReader<OnMousePressed>,
+    action_state: Res<ActionState>,
     mut current_selection: ResMut<CurrentSelection>,
 ) {
-    for mouse_press in on_mouse_pressed
-        .iter()
-        .filter(|mouse_press| mouse_press.button == MouseButton::Left)
-    {
-        if let Some(coord) = mouse_press.target.map(|target| target.in_coord) {
+    for mouse_press in on_mouse_pressed.iter().filter(|mouse_press| {
+        action_state.button_triggered(EditorAction::UseTool, mouse_press.button)
+    }) {
+        if let Some(coord) = mouse_press.target.and_then(|target| target.hit_block()) {
             toggle_coordinate_in_selection(coord, &mut current_selection);
         }
     }
@@ -67,34 +94,36 @@ fn handle_mouse_pressed(
 
 fn handle_drag_selection(
     mut on_mouse_drag: EventReader<OnMouseDrag>,
+    action_state: Res<ActionState>,
     mut current_selection: ResMut<CurrentSelection>,
+    mut on_select_request: EventWriter<OnSelectAreaRequest>,
 ) {
-    for mouse_drag in on_mouse_drag
-        .iter()
-        .filter(|mouse_drag| mouse_drag.button == MouseButton::Left && mouse_drag.drag_ended())
-    {
-        update_selection(
-            &mut current_selection,
-            mouse_drag.start.map(|target| target.in_coord),
-            mouse_drag.end.map(|target| target.in_coord),
-        );
-    }
-}
-
-fn update_selection(
-    current_selection: &mut CurrentSelection,
-    start: Option<Coordinate>,
-    end: Option<Coordinate>,
-) {
-    if let (Some(start), Some(end)) = (start, end) {
-        if start == end {
-            toggle_coordinate_in_selection(start, current_selection);
-        } else {
-            select_group(current_selection, start, end);
+    for mouse_drag in on_mouse_drag.iter().filter(|mouse_drag| {
+        action_state.button_triggered(EditorAction::UseTool, mouse_drag.button)
+            && mouse_drag.drag_ended()
+    }) {
+        let start = mouse_drag.start.and_then(|target| target.hit_block());
+        let end = mouse_drag.end.and_then(|target| target.hit_block());
+
+        if let (Some(start), Some(end)) = (start, end) {
+            if start == end {
+                toggle_coordinate_in_selection(start, &mut current_selection);
+            } else {
+                on_select_request.send(OnSelectAreaRequest::new(start, end));
+            }
         }
     }
 }
 
+fn handle_select_area_requests(
+    mut on_select_request: EventReader<OnSelectAreaRequest>,
+    mut current_selection: ResMut<CurrentSelection>,
+) {
+    for request in on_select_request.iter() {
+        select_group(&mut current_selection, request.start, request.end);
+    }
+}
+
 fn select_group(current_selection: &mut CurrentSelection, start: Coordinate, end: Coordinate) {
     current_selection.coordinates = get_coordinates_between(start, end);
 }
@@ -103,22 +132,30 @@ fn handle_color_change_input(
     current_selection: Res<CurrentSelection>,
     mut on_color_clicked: EventReader<OnColorClicked>,
     mut chunks: Query<&mut Chunk>,
+    color_library: Res<ColorLibrary>,
+    mut recent_colors: ResMut<RecentColors>,
 ) {
     let mut chunk = chunks.single_mut();
 
     for color_clicked in on_color_clicked.iter() {
-        let color = color_clicked.color;
+        let palette_index = color_clicked.index;
+
+        if color_library.color_at(palette_index).is_some() {
+            apply_color_to_selection(palette_index, &current_selection, &mut chunk);
 
-        apply_color_to_selection(color, &current_selection, &mut chunk);
+            if !current_selection.is_empty() {
+                recent_colors.push(palette_index);
+            }
+        }
     }
 }
 
 fn delete_selection_on_keypress(
-    input: Res<Input<KeyCode>>,
+    mut on_action: EventReader<OnEditorAction>,
     current_selection: ResMut<CurrentSelection>,
     mut chunks: Query<&mut Chunk>,
 ) {
-    if input.just_pressed(CLEAR_SELECTION_KEY) {
+    if action_triggered(&mut on_action, EditorAction::ClearSelection) {
         let mut chunk = chunks.single_mut();
 
         delete_selected_blocks(&current_selection, &mut chunk);
@@ -137,10 +174,14 @@ fn toggle_coordinate_in_selection(coord: Coordinate, selection: &mut CurrentSele
     }
 }
 
-fn apply_color_to_selection(color: Color, current_selection: &CurrentSelection, chunk: &mut Chunk) {
+fn apply_color_to_selection(
+    palette_index: usize,
+    current_selection: &CurrentSelection,
+    chunk: &mut Chunk,
+) {
     for coord in current_selection.coordinates.iter() {
-        if chunk.get_block(*coord).is_some() {
-            chunk.set_block(*coord, Some(Block::new(color)));
+        if let Some(block) = chunk.get_block(*coord) {
+            chunk.set_block(*coord, Some(block.with_palette_index(palette_index)));
         }
     }
 }
@@ -0,0 +1,353 @@
+mod settings_panel;
+
+use std::{fmt, path::Path};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game_systems::color_library::color_picker::{color_from_hex, color_to_hex};
+
+use self::settings_panel::SettingsPanelPlugin;
+use super::{
+    config_file::{
+        config_path, load_or_write_defaults, parse_config, save_config, ConfigFileError,
+    },
+    mouse_interaction::{
+        mouse_events::DEFAULT_DRAG_THRESHOLD, MouseInteraction, DEFAULT_MAX_INTERACTION_DISTANCE,
+    },
+};
+
+pub struct EditorSettingsPlugin;
+
+impl Plugin for EditorSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(SettingsPanelPlugin)
+            .add_event::<OnSaveSettingsRequest>()
+            .init_resource::<EditorSettings>()
+            .add_systems(Startup, load_editor_settings)
+            .add_systems(
+                Update,
+                (
+                    apply_editor_settings.run_if(resource_changed::<EditorSettings>()),
+                    handle_save_requests,
+                ),
+            );
+    }
+}
+
+const SETTINGS_FILE: &str = "editor_settings.ron";
+
+/// Tunables of the editor, stored in `editor_settings.ron` in the config directory.
+///
+/// Settings missing from the file keep their default value.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EditorSettings {
+    /// How far away blocks can be targeted, in world units.
+    reach_distance: f32,
+    /// How far the cursor moves on screen before a press becomes a drag, in logical pixels.
+    drag_threshold: f32,
+    target_color: Color,
+    placement_color: Color,
+    remove_color: Color,
+    normal_color: Color,
+    grid_color: Color,
+}
+
+impl Default for EditorSettings {
+    fn default() -> Self {
+        Self {
+            reach_distance: DEFAULT_MAX_INTERACTION_DISTANCE,
+            drag_threshold: DEFAULT_DRAG_THRESHOLD,
+            target_color: Color::WHITE,
+            placement_color: Color::CYAN,
+            remove_color: Color::RED,
+            normal_color: Color::BLUE,
+            grid_color: Color::rgba(0.7, 0.7, 0.7, 0.4),
+        }
+    }
+}
+
+impl EditorSettings {
+    pub fn target_color(&self) -> Color {
+        self.target_color
+    }
+
+    /// The color of previews for blocks that will be added.
+    pub fn placement_color(&self) -> Color {
+        self.placement_color
+    }
+
+    /// The color of previews for blocks that will be removed.
+    pub fn remove_color(&self) -> Color {
+        self.remove_color
+    }
+
+    pub fn normal_color(&self) -> Color {
+        self.normal_color
+    }
+
+    pub fn grid_color(&self) -> Color {
+        self.grid_color
+    }
+
+    /// The value of a setting as it's shown in the settings panel.
+    pub fn field_text(&self, field: SettingField) -> String {
+        match field {
+            SettingField::ReachDistance => format!("{:.1}", self.reach_distance),
+            SettingField::DragThreshold => format!("{:.1}", self.drag_threshold),
+            _ => self.color(field).map(color_to_hex).unwrap_or_default(),
+        }
+    }
+
+    /// Sets a setting from text, colors are written as hex and keep their transparency.
+    pub fn set_field(&mut self, field: SettingField, text: &str) -> Result<(), SettingsError> {
+        let invalid = || SettingsError::InvalidValue {
+            field,
+            text: text.trim().to_string(),
+        };
+
+        match field {
+            SettingField::ReachDistance => {
+                self.reach_distance = parse_distance(text)
+                    .filter(|distance| is_valid_reach(*distance))
+                    .ok_or_else(invalid)?;
+            }
+            SettingField::DragThreshold => {
+                self.drag_threshold = parse_distance(text).ok_or_else(invalid)?;
+            }
+            _ => {
+                let hex_color = color_from_hex(text).ok_or_else(invalid)?;
+
+                if let Some(color) = self.color_mut(field) {
+                    *color = hex_color.with_a(color.a());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn color(&self, field: SettingField) -> Option<Color> {
+        match field {
+            SettingField::TargetColor => Some(self.target_color),
+            SettingField::PlacementColor => Some(self.placement_color),
+            SettingField::RemoveColor => Some(self.remove_color),
+            SettingField::NormalColor => Some(self.normal_color),
+            SettingField::GridColor => Some(self.grid_color),
+            SettingField::ReachDistance | SettingField::DragThreshold => None,
+        }
+    }
+
+    fn color_mut(&mut self, field: SettingField) -> Option<&mut Color> {
+        match field {
+            SettingField::TargetColor => Some(&mut self.target_color),
+            SettingField::PlacementColor => Some(&mut self.placement_color),
+            SettingField::RemoveColor => Some(&mut self.remove_color),
+            SettingField::NormalColor => Some(&mut self.normal_color),
+            SettingField::GridColor => Some(&mut self.grid_color),
+            SettingField::ReachDistance | SettingField::DragThreshold => None,
+        }
+    }
+}
+
+/// A setting that can be edited in the settings panel.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingField {
+    ReachDistance,
+    DragThreshold,
+    TargetColor,
+    PlacementColor,
+    RemoveColor,
+    NormalColor,
+    GridColor,
+}
+
+impl SettingField {
+    pub const ALL: [SettingField; 7] = [
+        SettingField::ReachDistance,
+        SettingField::DragThreshold,
+        SettingField::TargetColor,
+        SettingField::PlacementColor,
+        SettingField::RemoveColor,
+        SettingField::NormalColor,
+        SettingField::GridColor,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SettingField::ReachDistance => "Reach distance",
+            SettingField::DragThreshold => "Drag threshold (px)",
+            SettingField::TargetColor => "Target color",
+            SettingField::PlacementColor => "Placement color",
+            SettingField::RemoveColor => "Remove color",
+            SettingField::NormalColor => "Normal color",
+            SettingField::GridColor => "Grid color",
+        }
+    }
+}
+
+/// Writes the current settings to disk.
+#[derive(Event)]
+pub struct OnSaveSettingsRequest;
+
+#[derive(Debug)]
+pub enum SettingsError {
+    File(ConfigFileError),
+    InvalidValue { field: SettingField, text: String },
+}
+
+impl From<ConfigFileError> for SettingsError {
+    fn from(error: ConfigFileError) -> Self {
+        SettingsError::File(error)
+    }
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::File(error) => write!(f, "{}", error),
+            SettingsError::InvalidValue { field, text } => {
+                write!(
+                    f,
+                    "'{}' is not a valid {}",
+                    text,
+                    field.label().to_lowercase()
+                )
+            }
+        }
+    }
+}
+
+fn load_editor_settings(mut settings: ResMut<EditorSettings>) {
+    let loaded = load_or_write_defaults(
+        &config_path(SETTINGS_FILE),
+        "editor settings",
+        |path| save_settings_to_file(&settings, path),
+        parse_settings,
+    );
+
+    if let Some(loaded) = loaded {
+        *settings = loaded;
+    }
+}
+
+fn apply_editor_settings(
+    settings: Res<EditorSettings>,
+    mut mouse_interaction: ResMut<MouseInteraction>,
+) {
+    mouse_interaction.set_max_interaction_distance(settings.reach_distance);
+    mouse_interaction.set_drag_threshold(settings.drag_threshold);
+}
+
+fn handle_save_requests(
+    settings: Res<EditorSettings>,
+    on_save_request: EventReader<OnSaveSettingsRequest>,
+) {
+    if on_save_request.is_empty() {
+        return;
+    }
+
+    if let Err(error) = save_settings_to_file(&settings, &config_path(SETTINGS_FILE)) {
+        println!("Error while saving editor settings: {}", error);
+    }
+}
+
+fn save_settings_to_file(settings: &EditorSettings, path: &Path) -> Result<(), SettingsError> {
+    Ok(save_config(settings, path)?)
+}
+
+/// Reads settings from a file, distances that can't be used are reported.
+fn parse_settings(file: &str) -> Result<EditorSettings, SettingsError> {
+    let settings = parse_config::<EditorSettings>(file)?;
+
+    if !is_valid_reach(settings.reach_distance) {
+        return Err(SettingsError::InvalidValue {
+            field: SettingField::ReachDistance,
+            text: settings.reach_distance.to_string(),
+        });
+    }
+
+    if !is_valid_distance(settings.drag_threshold) {
+        return Err(SettingsError::InvalidValue {
+            field: SettingField::DragThreshold,
+            text: settings.drag_threshold.to_string(),
+        });
+    }
+
+    Ok(settings)
+}
+
+fn parse_distance(text: &str) -> Option<f32> {
+    text.trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|distance| is_valid_distance(*distance))
+}
+
+fn is_valid_distance(distance: f32) -> bool {
+    distance.is_finite() && distance >= 0.0
+}
+
+/// Nothing could be targeted with a reach of zero.
+fn is_valid_reach(distance: f32) -> bool {
+    is_valid_distance(distance) && distance > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use ron::ser::PrettyConfig;
+
+    use super::*;
+
+    #[test]
+    fn default_settings_survive_a_round_trip() {
+        let settings = EditorSettings::default();
+
+        let serialized = ron::ser::to_string_pretty(&settings, PrettyConfig::default()).unwrap();
+
+        assert_eq!(parse_settings(&serialized).unwrap(), settings);
+    }
+
+    #[test]
+    fn missing_settings_keep_their_default() {
+        let settings = parse_settings("(reach_distance: 64.0)").unwrap();
+
+        assert_eq!(settings.reach_distance, 64.0);
+        assert_eq!(settings.drag_threshold, DEFAULT_DRAG_THRESHOLD);
+    }
+
+    #[test]
+    fn unusable_distances_are_rejected() {
+        let mut settings = EditorSettings::default();
+
+        assert!(parse_settings("(reach_distance: 0.0)").is_err());
+        assert!(settings
+            .set_field(SettingField::ReachDistance, "-5")
+            .is_err());
+        assert!(settings
+            .set_field(SettingField::DragThreshold, "far")
+            .is_err());
+        assert!(settings
+            .set_field(SettingField::DragThreshold, " 12 ")
+            .is_ok());
+
+        assert_eq!(settings.field_text(SettingField::DragThreshold), "12.0");
+        assert_eq!(settings.reach_distance, DEFAULT_MAX_INTERACTION_DISTANCE);
+    }
+
+    #[test]
+    fn colors_are_edited_as_hex_and_keep_transparency() {
+        let mut settings = EditorSettings::default();
+
+        settings
+            .set_field(SettingField::GridColor, "#44AA22")
+            .unwrap();
+
+        assert_eq!(settings.field_text(SettingField::GridColor), "#44AA22");
+        assert_eq!(
+            settings.grid_color().a(),
+            EditorSettings::default().grid_color().a()
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use bevy::prelude::*;
+
+use crate::{
+    game_systems::text_field::{
+        accepts_any, spawn_text_field, text_field_style, OnTextFieldSubmitted, TextField,
+    },
+    player::editor_actions::{action_triggered, EditorAction, OnEditorAction},
+};
+
+use super::{EditorSettings, OnSaveSettingsRequest, SettingField};
+
+pub struct SettingsPanelPlugin;
+
+impl Plugin for SettingsPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SettingsPanel>()
+            .add_systems(Startup, spawn_settings_panel)
+            .add_systems(
+                Update,
+                (
+                    toggle_settings_panel,
+                    update_panel_visibility,
+                    apply_submitted_settings,
+                    update_setting_fields.run_if(resource_changed::<EditorSettings>()),
+                )
+                    .chain(),
+            );
+    }
+}
+
+const MAX_VALUE_LENGTH: usize = 10;
+const LABEL_WIDTH: f32 = 160.0;
+const PANEL_COLOR: Color = Color::rgba(0.1, 0.1, 0.1, 0.9);
+
+#[derive(Resource, Default)]
+struct SettingsPanel {
+    is_open: bool,
+}
+
+#[derive(Component)]
+struct SettingsPanelNode;
+
+fn toggle_settings_panel(
+    mut on_action: EventReader<OnEditorAction>,
+    mut settings_panel: ResMut<SettingsPanel>,
+) {
+    if action_triggered(&mut on_action, EditorAction::ToggleSettings) {
+        settings_panel.is_open = !settings_panel.is_open;
+    }
+}
+
+fn update_panel_visibility(
+    settings_panel: Res<SettingsPanel>,
+    mut panels: Query<&mut Style, With<SettingsPanelNode>>,
+) {
+    let display = if settings_panel.is_open {
+        Display::Flex
+    } else {
+        Display::None
+    };
+
+    for mut style in panels.iter_mut() {
+        if style.display != display {
+            style.display = display;
+        }
+    }
+}
+
+/// Applies a submitted value and saves the settings, invalid values are reset to the current setting.
+fn apply_submitted_settings(
+    mut on_submitted: EventReader<OnTextFieldSubmitted>,
+    mut fields: Query<(&SettingField, &mut TextField)>,
+    mut settings: ResMut<EditorSettings>,
+    mut on_save_request: EventWriter<OnSaveSettingsRequest>,
+) {
+    for submitted in on_submitted.iter() {
+        let Ok((field, mut text_field)) = fields.get_mut(submitted.field) else {
+            continue;
+        };
+
+        match settings.set_field(*field, &submitted.text) {
+            Ok(()) => on_save_request.send(OnSaveSettingsRequest),
+            Err(error) => {
+                println!("{}", error);
+                text_field.text = settings.field_text(*field);
+            }
+        }
+    }
+}
+
+fn update_setting_fields(
+    settings: Res<EditorSettings>,
+    mut fields: Query<(&SettingField, &mut TextField)>,
+) {
+    for (field, mut text_field) in fields
+        .iter_mut()
+        .filter(|(_, text_field)| !text_field.is_focused())
+    {
+        text_field.text = settings.field_text(*field);
+    }
+}
+
+// UI
+
+fn spawn_settings_panel(mut commands: Commands, settings: Res<EditorSettings>) {
+    commands
+        .spawn((
+            Name::new("Settings panel"),
+            SettingsPanelNode,
+            Interaction::default(),
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(10.0),
+                    top: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(10.0)),
+                    row_gap: Val::Px(6.0),
+                    ..default()
+                },
+                background_color: PANEL_COLOR.into(),
+                ..default()
+            },
+        ))
+        .with_children(|panel| {
+            panel.spawn(TextBundle::from_section("Settings", text_field_style()));
+
+            for field in SettingField::ALL {
+                panel
+                    .spawn(NodeBundle {
+                        style: Style {
+                            column_gap: Val::Px(6.0),
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn(
+                            TextBundle::from_section(field.label(), text_field_style()).with_style(
+                                Style {
+                                    width: Val::Px(LABEL_WIDTH),
+                                    ..default()
+                                },
+                            ),
+                        );
+                        spawn_text_field(
+                            row,
+                            TextField::new(
+                                settings.field_text(field),
+                                MAX_VALUE_LENGTH,
+                                accepts_any,
+                            ),
+                            Val::Px(100.0),
+                            field,
+                        );
+                    });
+            }
+        });
+}
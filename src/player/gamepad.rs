@@ -0,0 +1,255 @@
+use bevy::{
+    input::{
+        gamepad::{GamepadAxisChangedEvent, GamepadButtonChangedEvent},
+        mouse::MouseMotion,
+    },
+    prelude::*,
+};
+
+use super::mouse_interaction::{MouseInteraction, TargetingMode};
+
+/*
+    Gamepad buttons are bound to actions in `editor_actions.rs` like keys are,
+    this module handles the sticks and the crosshair used to aim without a cursor.
+*/
+
+pub struct EditorGamepadPlugin;
+
+impl Plugin for EditorGamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_crosshair).add_systems(
+            Update,
+            (
+                switch_targeting_mode,
+                show_crosshair,
+                move_camera_with_sticks,
+            ),
+        );
+    }
+}
+
+/// Stick input below this length is ignored, so a resting stick doesn't drift.
+const STICK_DEAD_ZONE: f32 = 0.15;
+const CAMERA_MOVE_SPEED: f32 = 8.0;
+const CAMERA_LOOK_SPEED: f32 = 2.0;
+/// Keeps the camera from flipping over when looking straight up or down.
+const MAX_CAMERA_PITCH: f32 = 1.5;
+const CROSSHAIR_SIZE: f32 = 6.0;
+const CROSSHAIR_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.8);
+
+#[derive(Component)]
+struct Crosshair;
+
+fn spawn_crosshair(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Crosshair"),
+        Crosshair,
+        NodeBundle {
+            style: Style {
+                display: Display::None,
+                position_type: PositionType::Absolute,
+                left: Val::Percent(50.0),
+                top: Val::Percent(50.0),
+                width: Val::Px(CROSSHAIR_SIZE),
+                height: Val::Px(CROSSHAIR_SIZE),
+                margin: UiRect::new(
+                    Val::Px(-CROSSHAIR_SIZE / 2.0),
+                    Val::Px(0.0),
+                    Val::Px(-CROSSHAIR_SIZE / 2.0),
+                    Val::Px(0.0),
+                ),
+                ..default()
+            },
+            background_color: CROSSHAIR_COLOR.into(),
+            ..default()
+        },
+    ));
+}
+
+/// Aims with the crosshair once a gamepad is used, and goes back to the cursor when the mouse moves.
+fn switch_targeting_mode(
+    mut on_button_changed: EventReader<GamepadButtonChangedEvent>,
+    mut on_axis_changed: EventReader<GamepadAxisChangedEvent>,
+    mut on_mouse_motion: EventReader<MouseMotion>,
+    mut mouse_interaction: ResMut<MouseInteraction>,
+) {
+    let button_pressed = on_button_changed
+        .iter()
+        .filter(|event| event.value > 0.5)
+        .count()
+        > 0;
+    let stick_moved = on_axis_changed
+        .iter()
+        .filter(|event| event.value.abs() > STICK_DEAD_ZONE)
+        .count()
+        > 0;
+    let mouse_moved = on_mouse_motion.iter().count() > 0;
+
+    let targeting_mode = if mouse_moved {
+        TargetingMode::Cursor
+    } else if button_pressed || stick_moved {
+        TargetingMode::Crosshair
+    } else {
+        return;
+    };
+
+    if mouse_interaction.targeting_mode() != targeting_mode {
+        mouse_interaction.set_targeting_mode(targeting_mode);
+        println!("Targeting with: {:?}", targeting_mode);
+    }
+}
+
+fn show_crosshair(
+    mouse_interaction: Res<MouseInteraction>,
+    mut crosshairs: Query<&mut Style, With<Crosshair>>,
+) {
+    let display = match mouse_interaction.targeting_mode() {
+        TargetingMode::Cursor => Display::None,
+        TargetingMode::Crosshair => Display::Flex,
+    };
+
+    for mut style in crosshairs.iter_mut() {
+        if style.display != display {
+            style.display = display;
+        }
+    }
+}
+
+fn move_camera_with_sticks(
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    time: Res<Time>,
+    mouse_interaction: Res<MouseInteraction>,
+    mut transforms: Query<&mut Transform>,
+) {
+    let stick = |gamepad: Gamepad, x_axis: GamepadAxisType, y_axis: GamepadAxisType| {
+        Vec2::new(
+            axes.get(GamepadAxis::new(gamepad, x_axis)).unwrap_or(0.0),
+            axes.get(GamepadAxis::new(gamepad, y_axis)).unwrap_or(0.0),
+        )
+    };
+
+    let mut movement = Vec2::ZERO;
+    let mut look = Vec2::ZERO;
+
+    for gamepad in gamepads.iter() {
+        movement += apply_dead_zone(stick(
+            gamepad,
+            GamepadAxisType::LeftStickX,
+            GamepadAxisType::LeftStickY,
+        ));
+        look += apply_dead_zone(stick(
+            gamepad,
+            GamepadAxisType::RightStickX,
+            GamepadAxisType::RightStickY,
+        ));
+    }
+
+    if movement == Vec2::ZERO && look == Vec2::ZERO {
+        return;
+    }
+
+    let Some(mut transform) = mouse_interaction
+        .active_camera()
+        .and_then(|camera| transforms.get_mut(camera).ok())
+    else {
+        return;
+    };
+
+    fly_camera(&mut transform, movement, look, time.delta_seconds());
+}
+
+fn apply_dead_zone(stick: Vec2) -> Vec2 {
+    if stick.length() < STICK_DEAD_ZONE {
+        Vec2::ZERO
+    } else {
+        stick.clamp_length_max(1.0)
+    }
+}
+
+/// The left stick moves along the view direction, the right stick turns the camera.
+fn fly_camera(transform: &mut Transform, movement: Vec2, look: Vec2, delta_seconds: f32) {
+    let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+
+    let yaw = yaw - look.x * CAMERA_LOOK_SPEED * delta_seconds;
+    let pitch = (pitch + look.y * CAMERA_LOOK_SPEED * delta_seconds)
+        .clamp(-MAX_CAMERA_PITCH, MAX_CAMERA_PITCH);
+
+    transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
+
+    let direction = transform.forward() * movement.y + transform.right() * movement.x;
+    transform.translation += direction * CAMERA_MOVE_SPEED * delta_seconds;
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::gamepad::GamepadEvent;
+
+    use crate::player::test_input::{connect_gamepad, input_app};
+
+    use super::*;
+
+    fn gamepad_app() -> App {
+        let mut app = input_app();
+        app.init_resource::<MouseInteraction>()
+            .add_systems(Update, switch_targeting_mode);
+        connect_gamepad(&mut app, Gamepad::new(0));
+
+        app
+    }
+
+    #[test]
+    fn small_stick_movement_is_ignored() {
+        assert_eq!(apply_dead_zone(Vec2::new(0.1, 0.05)), Vec2::ZERO);
+        assert_eq!(apply_dead_zone(Vec2::new(0.0, 2.0)), Vec2::Y);
+    }
+
+    #[test]
+    fn left_stick_moves_where_the_camera_looks() {
+        let mut transform = Transform::default();
+
+        fly_camera(&mut transform, Vec2::Y, Vec2::ZERO, 1.0);
+
+        assert!(transform
+            .translation
+            .abs_diff_eq(Vec3::NEG_Z * CAMERA_MOVE_SPEED, 0.001));
+    }
+
+    #[test]
+    fn right_stick_pitch_is_clamped() {
+        let mut transform = Transform::default();
+
+        fly_camera(&mut transform, Vec2::ZERO, Vec2::Y, 10.0);
+
+        let (_, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+        assert!((pitch - MAX_CAMERA_PITCH).abs() < 0.001);
+    }
+
+    #[test]
+    fn gamepad_input_switches_to_crosshair() {
+        let mut app = gamepad_app();
+
+        app.world
+            .send_event(GamepadEvent::Axis(GamepadAxisChangedEvent::new(
+                Gamepad::new(0),
+                GamepadAxisType::RightStickX,
+                0.8,
+            )));
+        app.update();
+
+        assert_eq!(
+            app.world.resource::<MouseInteraction>().targeting_mode(),
+            TargetingMode::Crosshair
+        );
+
+        app.world.send_event(MouseMotion {
+            delta: Vec2::new(4.0, 0.0),
+        });
+        app.update();
+
+        assert_eq!(
+            app.world.resource::<MouseInteraction>().targeting_mode(),
+            TargetingMode::Cursor
+        );
+    }
+}
@@ -1,10 +1,12 @@
+pub mod construction_grid;
 pub mod mouse_events;
 pub mod mouse_target;
 
-use bevy::prelude::*;
+use bevy::{prelude::*, ui::UiSystem};
 
 use self::{
-    mouse_events::MouseEventsPlugin,
+    construction_grid::ConstructionGridPlugin,
+    mouse_events::{MouseEventsPlugin, DEFAULT_DRAG_THRESHOLD},
     mouse_target::{MouseTarget, MouseTargetPlugin},
 };
 
@@ -12,17 +14,34 @@ pub struct MouseInteractionPlugin;
 
 impl Plugin for MouseInteractionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((MouseTargetPlugin, MouseEventsPlugin))
+        app.add_plugins((MouseTargetPlugin, MouseEventsPlugin, ConstructionGridPlugin))
             .insert_resource(MouseInteraction::default())
-            .add_systems(Update, update_mouse_on_ui);
+            .add_systems(PreUpdate, update_mouse_on_ui.after(UiSystem::Focus));
     }
 }
 
+/// How far away blocks can be targeted, until the editor settings are loaded.
+pub const DEFAULT_MAX_INTERACTION_DISTANCE: f32 = 20.0;
+
+/// Where the interaction ray starts, the crosshair is used when playing with a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TargetingMode {
+    #[default]
+    Cursor,
+    /// Aim through the center of the screen.
+    Crosshair,
+}
+
 #[derive(Resource)]
 pub struct MouseInteraction {
     active_camera: Option<Entity>,
+    targeting_mode: TargetingMode,
+    /// Where the interaction ray goes through the screen, in logical pixels.
+    cursor_position: Option<Vec2>,
     mouse_on_ui: bool,
     max_interaction_distance: f32,
+    /// How far the cursor moves on screen before a press becomes a drag, in logical pixels.
+    drag_threshold: f32,
     ray_through_cursor: Option<Ray>,
     target: Option<MouseTarget>,
 }
@@ -31,22 +50,69 @@ impl MouseInteraction {
     pub fn set_active_camera(&mut self, camera_entity: Entity) {
         self.active_camera = Some(camera_entity);
     }
+
+    pub fn active_camera(&self) -> Option<Entity> {
+        self.active_camera
+    }
+
+    pub fn targeting_mode(&self) -> TargetingMode {
+        self.targeting_mode
+    }
+
+    pub fn set_targeting_mode(&mut self, targeting_mode: TargetingMode) {
+        self.targeting_mode = targeting_mode;
+    }
+
+    pub fn max_interaction_distance(&self) -> f32 {
+        self.max_interaction_distance
+    }
+
+    pub fn set_max_interaction_distance(&mut self, distance: f32) {
+        self.max_interaction_distance = distance;
+    }
+
+    pub fn set_drag_threshold(&mut self, threshold: f32) {
+        self.drag_threshold = threshold;
+    }
+
+    pub fn target(&self) -> Option<MouseTarget> {
+        self.target
+    }
+
+    pub fn mouse_on_ui(&self) -> bool {
+        self.mouse_on_ui
+    }
+
+    pub fn ray_through_cursor(&self) -> Option<Ray> {
+        self.ray_through_cursor
+    }
 }
 
 impl Default for MouseInteraction {
     fn default() -> Self {
         Self {
             active_camera: None,
+            targeting_mode: TargetingMode::Cursor,
+            cursor_position: None,
             mouse_on_ui: false,
-            max_interaction_distance: 20.0,
+            max_interaction_distance: DEFAULT_MAX_INTERACTION_DISTANCE,
+            drag_threshold: DEFAULT_DRAG_THRESHOLD,
             ray_through_cursor: None,
             target: None,
         }
     }
 }
 
-fn update_mouse_on_ui(mut mouse_interaction: ResMut<MouseInteraction>, nodes: Query<&Interaction>) {
-    mouse_interaction.mouse_on_ui = nodes.iter().any(|interaction| {
-        *interaction == Interaction::Hovered || *interaction == Interaction::Pressed
-    });
+/// Runs right after ui focus is updated, so presses are checked against the ui under the cursor this frame.
+///
+/// Panel roots have an `Interaction` too, so their padding and labels count as ui and not only their buttons.
+/// The crosshair ignores the cursor, so a cursor resting on ui doesn't block it.
+pub fn update_mouse_on_ui(
+    mut mouse_interaction: ResMut<MouseInteraction>,
+    nodes: Query<&Interaction>,
+) {
+    mouse_interaction.mouse_on_ui = mouse_interaction.targeting_mode == TargetingMode::Cursor
+        && nodes.iter().any(|interaction| {
+            *interaction == Interaction::Hovered || *interaction == Interaction::Pressed
+        });
 }
@@ -0,0 +1,194 @@
+use bevy::prelude::*;
+
+use super::mouse_target::MouseTarget;
+use crate::{
+    player::{
+        editor_actions::{EditorAction, OnEditorAction},
+        editor_settings::EditorSettings,
+    },
+    world::WorldSettings,
+};
+
+pub struct ConstructionGridPlugin;
+
+impl Plugin for ConstructionGridPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConstructionGrid>()
+            .add_systems(Update, (move_grid_on_actions, draw_construction_grid));
+    }
+}
+
+/// A horizontal plane to build on when the interaction ray doesn't hit any block.
+#[derive(Resource, Debug, Default)]
+pub struct ConstructionGrid {
+    /// The layer blocks placed on the grid end up in, the plane is the bottom of that layer.
+    height: usize,
+}
+
+impl ConstructionGrid {
+    fn plane_height(&self, block_scale: f32) -> f32 {
+        (self.height as f32 - 0.5) * block_scale
+    }
+
+    /// Targets the cell on the grid that the ray crosses, if it's inside the chunk and in reach.
+    pub fn target(
+        &self,
+        ray: Ray,
+        max_distance: f32,
+        world_settings: &WorldSettings,
+    ) -> Option<MouseTarget> {
+        let block_scale = world_settings.block_scale();
+        let plane_height = self.plane_height(block_scale);
+
+        // Looking at the grid from below targets the layer under it.
+        let normal = if ray.origin.y >= plane_height {
+            Vec3::Y
+        } else {
+            Vec3::NEG_Y
+        };
+
+        let distance = ray.intersect_plane(Vec3::Y * plane_height, normal)?;
+        if distance > max_distance {
+            return None;
+        }
+
+        let point = ray.get_point(distance);
+        let grid_size = world_settings.chunk_size() as f32 * block_scale;
+        let inside_chunk =
+            |value: f32| (-0.5 * block_scale..grid_size - 0.5 * block_scale).contains(&value);
+
+        if !inside_chunk(point.x) || !inside_chunk(point.z) {
+            return None;
+        }
+
+        let target = MouseTarget {
+            on_grid: true,
+            ..MouseTarget::from_point(point, normal, block_scale)
+        };
+
+        (normal == Vec3::Y || self.height > 0).then_some(target)
+    }
+}
+
+fn move_grid_on_actions(
+    mut on_action: EventReader<OnEditorAction>,
+    world_settings: Res<WorldSettings>,
+    mut construction_grid: ResMut<ConstructionGrid>,
+) {
+    for event in on_action.iter() {
+        let height = match event.action {
+            EditorAction::RaiseGrid => {
+                (construction_grid.height + 1).min(world_settings.chunk_size() - 1)
+            }
+            EditorAction::LowerGrid => construction_grid.height.saturating_sub(1),
+            _ => continue,
+        };
+
+        if construction_grid.height != height {
+            construction_grid.height = height;
+            println!("Construction grid height: {}", height);
+        }
+    }
+}
+
+fn draw_construction_grid(
+    construction_grid: Res<ConstructionGrid>,
+    world_settings: Res<WorldSettings>,
+    editor_settings: Res<EditorSettings>,
+    mut gizmos: Gizmos,
+) {
+    let block_scale = world_settings.block_scale();
+    let y = construction_grid.plane_height(block_scale);
+    let start = -0.5 * block_scale;
+    let end = (world_settings.chunk_size() as f32 - 0.5) * block_scale;
+
+    for line in 0..=world_settings.chunk_size() {
+        let offset = start + line as f32 * block_scale;
+
+        gizmos.line(
+            Vec3::new(offset, y, start),
+            Vec3::new(offset, y, end),
+            editor_settings.grid_color(),
+        );
+        gizmos.line(
+            Vec3::new(start, y, offset),
+            Vec3::new(end, y, offset),
+            editor_settings.grid_color(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::newtypes::{coordinate::Coordinate, direction::Direction};
+
+    use super::*;
+
+    fn ray_down_at(x: f32, z: f32) -> Ray {
+        Ray {
+            origin: Vec3::new(x, 10.0, z),
+            direction: Vec3::NEG_Y,
+        }
+    }
+
+    #[test]
+    fn ray_targets_the_cell_on_the_grid_layer() {
+        let grid = ConstructionGrid { height: 3 };
+
+        let target = grid.target(ray_down_at(4.2, 5.7), 100.0, &WorldSettings::new(16, 1.0));
+
+        assert_eq!(target.map(|target| target.normal), Some(Direction::Y));
+        assert_eq!(
+            target.map(|target| target.out_coord),
+            Some(Coordinate::new(4, 3, 6))
+        );
+        assert_eq!(target.and_then(|target| target.hit_block()), None);
+    }
+
+    #[test]
+    fn grid_accounts_for_block_scale() {
+        let grid = ConstructionGrid { height: 1 };
+
+        let target = grid.target(ray_down_at(4.2, 5.7), 100.0, &WorldSettings::new(16, 2.0));
+
+        assert_eq!(
+            target.map(|target| target.out_coord),
+            Some(Coordinate::new(2, 1, 3))
+        );
+    }
+
+    #[test]
+    fn grid_outside_the_chunk_or_reach_is_not_targeted() {
+        let grid = ConstructionGrid::default();
+        let world_settings = WorldSettings::new(16, 1.0);
+
+        assert_eq!(
+            grid.target(ray_down_at(-2.0, 3.0), 100.0, &world_settings),
+            None
+        );
+        assert_eq!(
+            grid.target(ray_down_at(3.0, 16.0), 100.0, &world_settings),
+            None
+        );
+        assert_eq!(
+            grid.target(ray_down_at(3.0, 3.0), 5.0, &world_settings),
+            None
+        );
+    }
+
+    #[test]
+    fn grid_seen_from_below_targets_the_layer_under_it() {
+        let grid = ConstructionGrid { height: 4 };
+        let ray = Ray {
+            origin: Vec3::new(2.0, 0.0, 2.0),
+            direction: Vec3::Y,
+        };
+
+        let target = grid.target(ray, 100.0, &WorldSettings::new(16, 1.0));
+
+        assert_eq!(
+            target.map(|target| target.out_coord),
+            Some(Coordinate::new(2, 3, 2))
+        );
+    }
+}
@@ -1,6 +1,11 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 
-use super::{mouse_target::MouseTarget, MouseInteraction};
+use crate::world::WorldSettings;
+
+use super::{
+    mouse_target::{update_mouse_target, MouseTarget},
+    MouseInteraction,
+};
 
 /*
     Tips for next iteration:
@@ -32,6 +37,12 @@ use super::{mouse_target::MouseTarget, MouseInteraction};
             - maybe send each action through a ActionEvent trait, and maybe if a systems accesses World it can loop though available events? I think this solves the code repetition
             - key-action mapping probably also needs &World access, since each object needs to check different world info
         - Is there too much input delay if there are multiple steps? Or can this all be done in one cycle?
+
+    The key - action mapping is now done in `player/editor_actions.rs`, mouse gestures still start here.
+
+    Each button is tracked on its own, a press becomes a drag once the cursor moves far enough on screen.
+    A gesture lasts from the first button press until every button is released,
+    gestures that start on ui belong to the ui and don't send any of the events below.
 */
 
 pub struct MouseEventsPlugin;
@@ -45,60 +56,82 @@ impl Plugin for MouseEventsPlugin {
                 Update,
                 (
                     handle_mouse_press,
-                    send_mouse_pressed_events,
                     send_mouse_drag_events,
+                    send_mouse_pressed_events,
+                    end_gestures,
                 )
-                    .chain(),
+                    .chain()
+                    .after(update_mouse_target),
             );
     }
 }
 
-const DISTANCE_THRESHOLD_FOR_DRAGGING: f32 = 0.3;
+/// How far the cursor moves on screen before a press becomes a drag, until the editor settings are loaded.
+pub const DEFAULT_DRAG_THRESHOLD: f32 = 6.0;
 
-#[derive(Resource, Default)]
-struct InteractionSelector {
-    // Interaction doesn't account for which mouse button is pressed or held.
+/// One button from its press until its release.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ButtonGesture {
+    start_cursor: Option<Vec2>,
     start_target: Option<MouseTarget>,
+    is_drag: bool,
 }
 
-impl InteractionSelector {
-    fn interaction_is_drag(&self, mouse_interaction: &MouseInteraction) -> bool {
-        match mouse_interaction.target {
-            Some(end_target) => match self.start_target {
-                Some(start_target) => {
-                    start_target.point.distance(end_target.point) > DISTANCE_THRESHOLD_FOR_DRAGGING
-                }
-                None => false,
-            },
-            None => false,
+impl ButtonGesture {
+    fn new(start_cursor: Option<Vec2>, start_target: Option<MouseTarget>) -> Self {
+        Self {
+            start_cursor,
+            start_target,
+            is_drag: false,
         }
     }
 
-    fn interaction_is_press(&self, mouse_interaction: &MouseInteraction) -> bool {
-        !self.interaction_is_drag(mouse_interaction)
+    fn passes_drag_threshold(&self, cursor: Option<Vec2>, threshold: f32) -> bool {
+        match (self.start_cursor, cursor) {
+            (Some(start), Some(current)) => start.distance(current) > threshold,
+            _ => false,
+        }
     }
 
-    fn reset(&mut self) {
-        self.start_target = None;
+    /// The current target, or where the ray crosses the plane of the start face when it doesn't hit anything.
+    fn end_target(
+        &self,
+        target: Option<MouseTarget>,
+        ray: Option<Ray>,
+        max_distance: f32,
+        block_scale: f32,
+    ) -> Option<MouseTarget> {
+        if target.is_some() {
+            return target;
+        }
+
+        self.start_target?
+            .project_on_face_plane(ray?, max_distance, block_scale)
     }
 }
 
+#[derive(Resource, Default)]
+struct InteractionSelector {
+    gestures: HashMap<MouseButton, ButtonGesture>,
+    /// Set when the gesture started on ui, world events are held back until every button is released.
+    captured_by_ui: bool,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum EventPhase {
-    // TODO: send mousedrag with started phase
-    #[allow(unused)]
     Started,
     Ongoing,
     Ended,
 }
 
+/// A click in the world, not sent for clicks that started on ui.
 #[derive(Event, Clone, Copy, Debug)]
 pub struct OnMousePressed {
     pub button: MouseButton,
-    pub on_ui: bool,
     pub target: Option<MouseTarget>,
 }
 
+/// A drag in the world, not sent for drags that started on ui.
 #[derive(Event, Clone, Copy, Debug)]
 pub struct OnMouseDrag {
     pub phase: EventPhase,
@@ -118,8 +151,67 @@ fn handle_mouse_press(
     mut interaction_selector: ResMut<InteractionSelector>,
     input: Res<Input<MouseButton>>,
 ) {
-    if input.get_just_pressed().len() > 0 {
-        interaction_selector.start_target = mouse_interaction.target;
+    if input.get_just_pressed().len() == 0 {
+        return;
+    }
+
+    let gesture_started = interaction_selector.gestures.is_empty();
+
+    if gesture_started {
+        interaction_selector.captured_by_ui = mouse_interaction.mouse_on_ui;
+    }
+
+    for button in input.get_just_pressed() {
+        interaction_selector.gestures.insert(
+            *button,
+            ButtonGesture::new(mouse_interaction.cursor_position, mouse_interaction.target),
+        );
+    }
+}
+
+fn send_mouse_drag_events(
+    mouse_interaction: Res<MouseInteraction>,
+    world_settings: Res<WorldSettings>,
+    mut interaction_selector: ResMut<InteractionSelector>,
+    input: Res<Input<MouseButton>>,
+    mut on_mouse_drag: EventWriter<OnMouseDrag>,
+) {
+    if interaction_selector.captured_by_ui {
+        return;
+    }
+
+    for (button, gesture) in interaction_selector.gestures.iter_mut() {
+        let released = input.just_released(*button);
+
+        let phase = if !gesture.is_drag {
+            if released
+                || !gesture.passes_drag_threshold(
+                    mouse_interaction.cursor_position,
+                    mouse_interaction.drag_threshold,
+                )
+            {
+                continue;
+            }
+
+            gesture.is_drag = true;
+            EventPhase::Started
+        } else if released {
+            EventPhase::Ended
+        } else {
+            EventPhase::Ongoing
+        };
+
+        on_mouse_drag.send(OnMouseDrag {
+            phase,
+            button: *button,
+            start: gesture.start_target,
+            end: gesture.end_target(
+                mouse_interaction.target,
+                mouse_interaction.ray_through_cursor,
+                mouse_interaction.max_interaction_distance,
+                world_settings.block_scale(),
+            ),
+        });
     }
 }
 
@@ -129,47 +221,233 @@ fn send_mouse_pressed_events(
     input: Res<Input<MouseButton>>,
     mut on_mouse_pressed: EventWriter<OnMousePressed>,
 ) {
-    for release in input.get_just_released() {
-        if interaction_selector.interaction_is_press(&mouse_interaction) {
+    if interaction_selector.captured_by_ui {
+        return;
+    }
+
+    for released_button in input.get_just_released() {
+        let is_press = interaction_selector
+            .gestures
+            .get(released_button)
+            .is_some_and(|gesture| !gesture.is_drag);
+
+        if is_press {
             on_mouse_pressed.send(OnMousePressed {
-                button: *release,
-                on_ui: mouse_interaction.mouse_on_ui,
+                button: *released_button,
                 target: mouse_interaction.target,
             });
         }
     }
 }
 
-fn send_mouse_drag_events(
-    mouse_interaction: Res<MouseInteraction>,
+/// Forgets released buttons, the ui keeps the gesture until the last button is released.
+fn end_gestures(
     mut interaction_selector: ResMut<InteractionSelector>,
     input: Res<Input<MouseButton>>,
-    mut on_mouse_drag: EventWriter<OnMouseDrag>,
 ) {
-    for held_button in input.get_pressed() {
-        if interaction_selector.interaction_is_drag(&mouse_interaction) {
-            on_mouse_drag.send({
-                OnMouseDrag {
-                    phase: EventPhase::Ongoing,
-                    button: *held_button,
-                    start: interaction_selector.start_target,
-                    end: mouse_interaction.target,
-                }
-            });
-        }
+    interaction_selector
+        .gestures
+        .retain(|button, _| input.pressed(*button));
+
+    if interaction_selector.gestures.is_empty() {
+        interaction_selector.captured_by_ui = false;
     }
+}
 
-    for released_button in input.get_just_released() {
-        if interaction_selector.interaction_is_drag(&mouse_interaction) {
-            on_mouse_drag.send({
-                OnMouseDrag {
-                    phase: EventPhase::Ended,
-                    button: *released_button,
-                    start: interaction_selector.start_target,
-                    end: mouse_interaction.target,
-                }
-            });
-            interaction_selector.reset();
+#[cfg(test)]
+mod tests {
+    use bevy::input::{mouse::MouseButtonInput, ButtonState, InputPlugin};
+
+    use crate::newtypes::{coordinate::Coordinate, direction::Direction};
+
+    use super::*;
+
+    fn mouse_events_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin, MouseEventsPlugin))
+            .init_resource::<MouseInteraction>()
+            .insert_resource(WorldSettings::new(16, 1.0));
+        app
+    }
+
+    fn target_at(x: usize) -> MouseTarget {
+        MouseTarget {
+            point: Vec3::new(x as f32, 0.5, 0.0),
+            normal: Direction::Y,
+            in_coord: Coordinate::new(x, 0, 0),
+            out_coord: Coordinate::new(x, 1, 0),
+            on_grid: false,
         }
     }
+
+    /// Moves the cursor 10 pixels for every block, so moving to another block passes the drag threshold.
+    fn move_cursor(app: &mut App, on_ui: bool, target: Option<MouseTarget>) {
+        let mut mouse_interaction = app.world.resource_mut::<MouseInteraction>();
+        mouse_interaction.mouse_on_ui = on_ui;
+        mouse_interaction.target = target;
+        mouse_interaction.cursor_position =
+            target.map(|target| Vec2::new(target.point.x * 10.0, 0.0));
+    }
+
+    fn change_button(app: &mut App, button: MouseButton, state: ButtonState) {
+        app.world.send_event(MouseButtonInput {
+            button,
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+        app.update();
+    }
+
+    fn sent_presses(app: &mut App) -> Vec<OnMousePressed> {
+        app.world
+            .resource_mut::<Events<OnMousePressed>>()
+            .drain()
+            .collect()
+    }
+
+    fn sent_drags(app: &mut App) -> Vec<OnMouseDrag> {
+        app.world
+            .resource_mut::<Events<OnMouseDrag>>()
+            .drain()
+            .collect()
+    }
+
+    #[test]
+    fn click_that_starts_on_ui_is_ignored() {
+        let mut app = mouse_events_app();
+
+        move_cursor(&mut app, true, None);
+        change_button(&mut app, MouseButton::Left, ButtonState::Pressed);
+        move_cursor(&mut app, false, Some(target_at(0)));
+        change_button(&mut app, MouseButton::Left, ButtonState::Released);
+
+        assert!(sent_presses(&mut app).is_empty());
+        assert!(sent_drags(&mut app).is_empty());
+    }
+
+    #[test]
+    fn drag_that_starts_on_ui_is_ignored() {
+        let mut app = mouse_events_app();
+
+        // The target from before the cursor reached the ui is still set.
+        move_cursor(&mut app, true, Some(target_at(0)));
+        change_button(&mut app, MouseButton::Left, ButtonState::Pressed);
+        move_cursor(&mut app, false, Some(target_at(5)));
+        app.update();
+        change_button(&mut app, MouseButton::Left, ButtonState::Released);
+
+        assert!(sent_presses(&mut app).is_empty());
+        assert!(sent_drags(&mut app).is_empty());
+    }
+
+    #[test]
+    fn second_button_doesnt_end_ui_capture() {
+        let mut app = mouse_events_app();
+
+        move_cursor(&mut app, true, None);
+        change_button(&mut app, MouseButton::Left, ButtonState::Pressed);
+        move_cursor(&mut app, false, Some(target_at(0)));
+        change_button(&mut app, MouseButton::Right, ButtonState::Pressed);
+        change_button(&mut app, MouseButton::Right, ButtonState::Released);
+        change_button(&mut app, MouseButton::Left, ButtonState::Released);
+
+        assert!(sent_presses(&mut app).is_empty());
+        assert!(sent_drags(&mut app).is_empty());
+    }
+
+    #[test]
+    fn world_click_after_ui_gesture_is_sent() {
+        let mut app = mouse_events_app();
+
+        move_cursor(&mut app, true, None);
+        change_button(&mut app, MouseButton::Left, ButtonState::Pressed);
+        change_button(&mut app, MouseButton::Left, ButtonState::Released);
+        sent_presses(&mut app);
+
+        move_cursor(&mut app, false, Some(target_at(0)));
+        change_button(&mut app, MouseButton::Left, ButtonState::Pressed);
+        change_button(&mut app, MouseButton::Left, ButtonState::Released);
+
+        assert_eq!(sent_presses(&mut app).len(), 1);
+        assert!(sent_drags(&mut app).is_empty());
+    }
+
+    #[test]
+    fn drag_is_sent_in_phases() {
+        let mut app = mouse_events_app();
+
+        move_cursor(&mut app, false, Some(target_at(0)));
+        change_button(&mut app, MouseButton::Left, ButtonState::Pressed);
+        move_cursor(&mut app, false, Some(target_at(2)));
+        app.update();
+
+        // Events only last two frames, so they are collected after every frame.
+        let mut drags = sent_drags(&mut app);
+        app.update();
+        drags.extend(sent_drags(&mut app));
+        change_button(&mut app, MouseButton::Left, ButtonState::Released);
+        drags.extend(sent_drags(&mut app));
+
+        let phases: Vec<EventPhase> = drags.iter().map(|drag| drag.phase).collect();
+
+        assert_eq!(
+            phases,
+            vec![EventPhase::Started, EventPhase::Ongoing, EventPhase::Ended]
+        );
+        assert!(sent_presses(&mut app).is_empty());
+    }
+
+    #[test]
+    fn small_cursor_movement_stays_a_press() {
+        let mut app = mouse_events_app();
+
+        move_cursor(&mut app, false, Some(target_at(0)));
+        change_button(&mut app, MouseButton::Left, ButtonState::Pressed);
+        app.world.resource_mut::<MouseInteraction>().cursor_position =
+            Some(Vec2::new(DEFAULT_DRAG_THRESHOLD - 1.0, 0.0));
+        change_button(&mut app, MouseButton::Left, ButtonState::Released);
+
+        assert_eq!(sent_presses(&mut app).len(), 1);
+        assert!(sent_drags(&mut app).is_empty());
+    }
+
+    #[test]
+    fn buttons_are_tracked_separately() {
+        let mut app = mouse_events_app();
+
+        move_cursor(&mut app, false, Some(target_at(0)));
+        change_button(&mut app, MouseButton::Left, ButtonState::Pressed);
+        move_cursor(&mut app, false, Some(target_at(3)));
+        change_button(&mut app, MouseButton::Right, ButtonState::Pressed);
+        change_button(&mut app, MouseButton::Right, ButtonState::Released);
+        change_button(&mut app, MouseButton::Left, ButtonState::Released);
+
+        let presses = sent_presses(&mut app);
+        let ended: Vec<OnMouseDrag> = sent_drags(&mut app)
+            .into_iter()
+            .filter(|drag| drag.drag_ended())
+            .collect();
+
+        assert_eq!(presses.len(), 1);
+        assert_eq!(presses[0].button, MouseButton::Right);
+        assert_eq!(ended.len(), 1);
+        assert_eq!(ended[0].button, MouseButton::Left);
+        assert_eq!(ended[0].start, Some(target_at(0)));
+    }
+
+    #[test]
+    fn drag_over_empty_space_follows_the_start_face() {
+        let gesture = ButtonGesture::new(Some(Vec2::ZERO), Some(target_at(0)));
+        let ray = Ray {
+            origin: Vec3::new(4.0, 5.0, 0.0),
+            direction: Vec3::NEG_Y,
+        };
+
+        let end = gesture.end_target(None, Some(ray), 20.0, 1.0).unwrap();
+
+        assert_eq!(end.point, Vec3::new(4.0, 0.5, 0.0));
+        assert_eq!(end.in_coord, Coordinate::new(4, 0, 0));
+        assert_eq!(end.out_coord, Coordinate::new(4, 1, 0));
+        assert_eq!(gesture.end_target(None, Some(ray), 2.0, 1.0), None);
+    }
 }
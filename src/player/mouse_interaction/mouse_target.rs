@@ -1,9 +1,10 @@
 use bevy::{prelude::*, window::PrimaryWindow};
 use bevy_rapier3d::prelude::*;
 
-use super::MouseInteraction;
+use super::{construction_grid::ConstructionGrid, MouseInteraction, TargetingMode};
 use crate::{
     newtypes::{coordinate::Coordinate, direction::Direction},
+    player::editor_settings::EditorSettings,
     world::WorldSettings,
 };
 
@@ -17,7 +18,8 @@ impl Plugin for MouseTargetPlugin {
                 update_interaction_ray,
                 update_mouse_target,
                 draw_target_block_gizmos,
-            ),
+            )
+                .chain(),
         );
     }
 }
@@ -30,23 +32,59 @@ pub struct MouseTarget {
     pub normal: Direction,
     pub in_coord: Coordinate,
     pub out_coord: Coordinate,
+    /// The construction grid was hit instead of a block, so `in_coord` is an empty cell.
+    pub on_grid: bool,
 }
 
+/// How far the hit point is moved into the hit block, so points on slanted or inner faces still land in their block.
+const SURFACE_OFFSET: f32 = 0.01;
+
 impl MouseTarget {
     fn from_raycast(intersection: RayIntersection, block_scale: f32) -> Self {
-        let point = intersection.point;
-        let normal = intersection.normal;
+        Self::from_point(intersection.point, intersection.normal, block_scale)
+    }
 
-        let in_position = (point / block_scale - normal / 2.0).round();
-        let out_position = (point / block_scale + normal / 2.0).round();
+    /// Targets the block behind a point on a surface, the surface normal doesn't have to be along an axis.
+    pub fn from_point(point: Vec3, surface_normal: Vec3, block_scale: f32) -> Self {
+        let normal = Direction::nearest_axis(surface_normal);
+
+        let in_position = (point / block_scale - surface_normal * SURFACE_OFFSET).round();
+        let out_position = in_position + normal.to_vector();
 
         Self {
             point,
-            normal: Direction::from(normal),
+            normal,
             in_coord: Coordinate::from(in_position),
             out_coord: Coordinate::from(out_position),
+            on_grid: false,
         }
     }
+
+    /// The coordinate of the block that was hit, grid hits have no block.
+    pub fn hit_block(&self) -> Option<Coordinate> {
+        (!self.on_grid).then_some(self.in_coord)
+    }
+
+    /// Targets where the ray crosses the plane of this target's face, if that's within the max distance.
+    ///
+    /// Rays almost parallel to the plane cross it very far away, those are dropped by the distance.
+    pub fn project_on_face_plane(
+        &self,
+        ray: Ray,
+        max_distance: f32,
+        block_scale: f32,
+    ) -> Option<MouseTarget> {
+        let normal = self.normal.to_vector();
+
+        let distance = ray
+            .intersect_plane(self.point, normal)
+            .filter(|distance| *distance <= max_distance)?;
+
+        Some(MouseTarget {
+            on_grid: self.on_grid,
+            ..MouseTarget::from_point(ray.get_point(distance), normal, block_scale)
+        })
+    }
 }
 
 #[derive(Event, Clone, Copy, Debug)]
@@ -61,19 +99,23 @@ fn update_interaction_ray(
 ) {
     let window = window.single();
 
-    mouse_interaction.ray_through_cursor = get_ray_of_active_camera(&mouse_interaction, &cameras, window);
+    mouse_interaction.cursor_position =
+        get_screen_position(mouse_interaction.targeting_mode, window);
+    mouse_interaction.ray_through_cursor = get_ray_of_active_camera(&mouse_interaction, &cameras);
 }
 
-fn update_mouse_target(
+pub fn update_mouse_target(
     rapier_context: Res<RapierContext>,
     world_settings: Res<WorldSettings>,
+    construction_grid: Res<ConstructionGrid>,
     mut mouse_interaction: ResMut<MouseInteraction>,
     mut on_target_changed: EventWriter<OnMouseTargetChanged>,
 ) {
     let target_block = calculate_mouse_target(
         &rapier_context,
         &mouse_interaction,
-        world_settings.block_scale(),
+        &world_settings,
+        &construction_grid,
     );
 
     if mouse_interaction.target != target_block {
@@ -86,10 +128,17 @@ fn update_mouse_target(
 
 // Utility
 
+/// The crosshair aims through the center of the screen.
+fn get_screen_position(targeting_mode: TargetingMode, window: &Window) -> Option<Vec2> {
+    match targeting_mode {
+        TargetingMode::Cursor => window.cursor_position(),
+        TargetingMode::Crosshair => Some(Vec2::new(window.width(), window.height()) / 2.0),
+    }
+}
+
 fn get_ray_of_active_camera(
     mouse_interaction: &MouseInteraction,
     cameras: &Query<(&Camera, &GlobalTransform)>,
-    window: &Window,
 ) -> Option<Ray> {
     if mouse_interaction.mouse_on_ui {
         return None;
@@ -97,29 +146,26 @@ fn get_ray_of_active_camera(
 
     let (camera, transform) = cameras.get(mouse_interaction.active_camera?).ok()?;
 
-    get_cursor_as_ray(camera, transform, window)
-}
-
-fn get_cursor_as_ray(
-    camera: &Camera,
-    global_transform: &GlobalTransform,
-    window: &Window,
-) -> Option<Ray> {
-    camera.viewport_to_world(global_transform, window.cursor_position()?)
+    camera.viewport_to_world(transform, mouse_interaction.cursor_position?)
 }
 
 fn calculate_mouse_target(
     rapier: &RapierContext,
     mouse_interaction: &MouseInteraction,
-    block_scale: f32,
+    world_settings: &WorldSettings,
+    construction_grid: &ConstructionGrid,
 ) -> Option<MouseTarget> {
-    let intersection = raycast_from_ray(
-        rapier,
-        mouse_interaction.ray_through_cursor?,
-        mouse_interaction.max_interaction_distance,
-    )?;
-
-    Some(MouseTarget::from_raycast(intersection, block_scale))
+    let ray = mouse_interaction.ray_through_cursor?;
+    let max_distance = mouse_interaction.max_interaction_distance;
+
+    match raycast_from_ray(rapier, ray, max_distance) {
+        Some(intersection) => Some(MouseTarget::from_raycast(
+            intersection,
+            world_settings.block_scale(),
+        )),
+        // Builds on empty space when no block is hit.
+        None => construction_grid.target(ray, max_distance, world_settings),
+    }
 }
 
 fn raycast_from_ray(
@@ -143,15 +189,26 @@ fn raycast_from_ray(
 fn draw_target_block_gizmos(
     mouse_interaction: Res<MouseInteraction>,
     world_settings: Res<WorldSettings>,
+    editor_settings: Res<EditorSettings>,
     mut gizmos: Gizmos,
 ) {
     if let Some(target) = mouse_interaction.target {
         let in_position = world_settings.coordinate_to_position(target.in_coord);
         let out_position = world_settings.coordinate_to_position(target.out_coord);
 
-        gizmos.cuboid(Transform::from_translation(in_position), Color::WHITE);
-        gizmos.cuboid(Transform::from_translation(out_position), Color::CYAN);
-        gizmos.ray(in_position, target.normal.to_vector(), Color::BLUE);
+        gizmos.cuboid(
+            Transform::from_translation(in_position),
+            editor_settings.target_color(),
+        );
+        gizmos.cuboid(
+            Transform::from_translation(out_position),
+            editor_settings.placement_color(),
+        );
+        gizmos.ray(
+            in_position,
+            target.normal.to_vector(),
+            editor_settings.normal_color(),
+        );
     }
 }
 
@@ -186,7 +243,7 @@ mod tests {
     fn target_block_accounts_for_block_scale() {
         let intersection = RayIntersection {
             normal: Vec3::Y,
-            point: Vec3::new(2.0, 1.5, 2.0),
+            point: Vec3::new(2.0, 1.0, 2.0),
             toi: 3.0,
             feature: FeatureId::default(),
         };
@@ -198,4 +255,20 @@ mod tests {
         assert_eq!(target.in_coord, Coordinate::new(1, 0, 1));
         assert_eq!(target.out_coord, Coordinate::new(1, 1, 1));
     }
+
+    #[test]
+    fn slanted_hit_targets_the_block_it_belongs_to() {
+        let intersection = RayIntersection {
+            normal: Vec3::new(0.0, 0.6, 0.8),
+            point: Vec3::new(2.0, 1.6, 2.4),
+            toi: 3.0,
+            feature: FeatureId::default(),
+        };
+
+        let target = MouseTarget::from_raycast(intersection, 1.0);
+
+        assert_eq!(target.normal, Direction::Z);
+        assert_eq!(target.in_coord, Coordinate::new(2, 2, 2));
+        assert_eq!(target.out_coord, Coordinate::new(2, 2, 3));
+    }
 }
@@ -0,0 +1,41 @@
+use bevy::{
+    input::{
+        gamepad::{GamepadConnection, GamepadConnectionEvent, GamepadEvent, GamepadInfo},
+        keyboard::KeyboardInput,
+        ButtonState, InputPlugin,
+    },
+    prelude::*,
+};
+
+/*
+    Helpers for tests that run player systems in a headless app and feed them input events.
+*/
+
+/// A headless app with the input resources, the systems under test are added by the caller.
+pub fn input_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, InputPlugin));
+    app
+}
+
+/// Connects the gamepad and runs an update, so its buttons and axes are registered.
+pub fn connect_gamepad(app: &mut App, gamepad: Gamepad) {
+    app.world
+        .send_event(GamepadEvent::Connection(GamepadConnectionEvent::new(
+            gamepad,
+            GamepadConnection::Connected(GamepadInfo {
+                name: "Test gamepad".to_string(),
+            }),
+        )));
+    app.update();
+}
+
+/// Sends a key press, it's handled on the next update.
+pub fn press_key(app: &mut App, key_code: KeyCode) {
+    app.world.send_event(KeyboardInput {
+        scan_code: 0,
+        key_code: Some(key_code),
+        state: ButtonState::Pressed,
+        window: Entity::PLACEHOLDER,
+    });
+}
@@ -33,6 +33,10 @@ impl WorldSettings {
         }
     }
 
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
     pub fn block_scale(&self) -> f32 {
         self.block_scale
     }
@@ -1,13 +1,54 @@
-use bevy::prelude::*;
+pub mod block_shape;
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+use self::block_shape::{BlockOrientation, BlockShape};
+
+/// A voxel, its color is the entry at `palette_index` in the scene's `ColorLibrary`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
 pub struct Block {
-    pub color: Color,
+    palette_index: u16,
+    #[serde(default)]
+    shape: BlockShape,
+    #[serde(default)]
+    orientation: BlockOrientation,
 }
 
 impl Block {
-    pub fn new(color: Color) -> Self {
-        Self { color }
+    /// Palettes are capped at `ColorLibrary::MAX_COLORS`, so the index fits in 16 bits.
+    pub fn new(palette_index: usize) -> Self {
+        debug_assert!(palette_index <= u16::MAX as usize);
+
+        Self {
+            palette_index: palette_index as u16,
+            shape: BlockShape::default(),
+            orientation: BlockOrientation::default(),
+        }
+    }
+
+    pub fn with_shape(mut self, shape: BlockShape, orientation: BlockOrientation) -> Self {
+        self.shape = shape;
+        self.orientation = orientation;
+        self
+    }
+
+    /// Returns this block with another color, keeping its shape.
+    pub fn with_palette_index(mut self, palette_index: usize) -> Self {
+        debug_assert!(palette_index <= u16::MAX as usize);
+
+        self.palette_index = palette_index as u16;
+        self
+    }
+
+    pub fn palette_index(&self) -> usize {
+        self.palette_index as usize
+    }
+
+    pub fn shape(&self) -> BlockShape {
+        self.shape
+    }
+
+    pub fn orientation(&self) -> BlockOrientation {
+        self.orientation
     }
 }
@@ -0,0 +1,164 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::newtypes::direction::Direction;
+
+/// The geometry of a block, before it's rotated by its `BlockOrientation`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
+pub enum BlockShape {
+    #[default]
+    Full,
+    /// The lower half of a block.
+    Slab,
+    /// A slab with a half-height step on its back half.
+    Stair,
+    /// A ramp that rises towards the back.
+    Slope,
+    /// An outer corner of two slopes, rising towards the back left corner.
+    CornerSlope,
+}
+
+impl BlockShape {
+    pub const ALL: [BlockShape; 5] = [
+        BlockShape::Full,
+        BlockShape::Slab,
+        BlockShape::Stair,
+        BlockShape::Slope,
+        BlockShape::CornerSlope,
+    ];
+
+    pub fn next(&self) -> Self {
+        match self {
+            BlockShape::Full => BlockShape::Slab,
+            BlockShape::Slab => BlockShape::Stair,
+            BlockShape::Stair => BlockShape::Slope,
+            BlockShape::Slope => BlockShape::CornerSlope,
+            BlockShape::CornerSlope => BlockShape::Full,
+        }
+    }
+}
+
+/// The horizontal direction the back of a block points to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Facing {
+    /// Towards negative Z.
+    #[default]
+    North,
+    /// Towards positive X.
+    East,
+    /// Towards positive Z.
+    South,
+    /// Towards negative X.
+    West,
+}
+
+impl Facing {
+    /// Returns the facing closest to the horizontal part of the vector.
+    pub fn from_vector(vector: Vec3) -> Self {
+        if vector.x.abs() > vector.z.abs() {
+            if vector.x > 0.0 {
+                Facing::East
+            } else {
+                Facing::West
+            }
+        } else if vector.z > 0.0 {
+            Facing::South
+        } else {
+            Facing::North
+        }
+    }
+
+    /// Rotation around the Y axis that turns north into this facing.
+    fn angle(&self) -> f32 {
+        match self {
+            Facing::North => 0.0,
+            Facing::West => FRAC_PI_2,
+            Facing::South => PI,
+            Facing::East => -FRAC_PI_2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct BlockOrientation {
+    pub facing: Facing,
+    pub upside_down: bool,
+}
+
+impl BlockOrientation {
+    pub fn new(facing: Facing, upside_down: bool) -> Self {
+        Self {
+            facing,
+            upside_down,
+        }
+    }
+
+    /// Orients a block that is placed against a face with the given normal while looking along `view_direction`,
+    /// the back faces away from the viewer and blocks placed under a face hang upside down.
+    pub fn from_placement(normal: Direction, view_direction: Vec3) -> Self {
+        Self::new(
+            Facing::from_vector(view_direction),
+            normal == Direction::NEG_Y,
+        )
+    }
+
+    pub fn rotation(&self) -> Quat {
+        let flip = if self.upside_down {
+            Quat::from_rotation_z(PI)
+        } else {
+            Quat::IDENTITY
+        };
+
+        Quat::from_rotation_y(self.facing.angle()) * flip
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn facing_follows_the_strongest_horizontal_axis() {
+        assert_eq!(
+            Facing::from_vector(Vec3::new(0.2, -0.9, -0.4)),
+            Facing::North
+        );
+        assert_eq!(Facing::from_vector(Vec3::new(0.7, 0.0, 0.3)), Facing::East);
+        assert_eq!(Facing::from_vector(Vec3::new(-0.7, 0.0, 0.3)), Facing::West);
+        assert_eq!(Facing::from_vector(Vec3::new(0.1, 0.0, 0.3)), Facing::South);
+    }
+
+    #[test]
+    fn rotation_turns_the_back_towards_the_facing() {
+        let cases = [
+            (Facing::North, Vec3::NEG_Z),
+            (Facing::East, Vec3::X),
+            (Facing::South, Vec3::Z),
+            (Facing::West, Vec3::NEG_X),
+        ];
+
+        for (facing, expected) in cases {
+            let back = BlockOrientation::new(facing, false).rotation() * Vec3::NEG_Z;
+
+            assert!(back.abs_diff_eq(expected, 0.0001), "{:?}", facing);
+        }
+    }
+
+    #[test]
+    fn upside_down_keeps_the_facing_and_flips_the_top() {
+        let rotation = BlockOrientation::new(Facing::East, true).rotation();
+
+        assert!((rotation * Vec3::NEG_Z).abs_diff_eq(Vec3::X, 0.0001));
+        assert!((rotation * Vec3::Y).abs_diff_eq(Vec3::NEG_Y, 0.0001));
+    }
+
+    #[test]
+    fn placing_under_a_face_hangs_upside_down() {
+        let orientation = BlockOrientation::from_placement(Direction::NEG_Y, Vec3::NEG_X);
+
+        assert_eq!(orientation, BlockOrientation::new(Facing::West, true));
+        assert!(!BlockOrientation::from_placement(Direction::Y, Vec3::NEG_X).upside_down);
+    }
+}
@@ -1,8 +1,13 @@
-use bevy::prelude::*;
+mod shape_geometry;
+
+use bevy::{prelude::*, utils::HashMap};
 use bevy_rapier3d::prelude::*;
 
+use crate::game_systems::color_library::{ColorLibrary, PaletteColor};
+
+use self::shape_geometry::{build_shape_collider, build_shape_mesh};
 use super::{
-    block::Block,
+    block::{block_shape::BlockShape, Block},
     chunk::{Chunk, OnRedrawChunkRequest},
     WorldSettings,
 };
@@ -11,27 +16,54 @@ pub struct WorldBuilderPlugin;
 
 impl Plugin for WorldBuilderPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_chunk)
-            .add_systems(Update, redraw_requested_chunks);
+        app.add_systems(Startup, spawn_chunk).add_systems(
+            Update,
+            (
+                redraw_chunks_on_palette_change.run_if(resource_changed::<ColorLibrary>()),
+                redraw_requested_chunks,
+            )
+                .chain(),
+        );
     }
 }
 
+/// Color of blocks whose palette index doesn't exist in the current palette.
+const MISSING_COLOR: Color = Color::FUCHSIA;
+
 fn spawn_chunk(mut commands: Commands, world_settings: Res<WorldSettings>) {
     let ground_height = 2;
 
     commands.spawn((
         Name::new("Chunk"),
         SpatialBundle::default(),
-        Chunk::flat_ground(ground_height, Color::LIME_GREEN, world_settings.chunk_size),
+        Chunk::flat_ground(ground_height, 0, world_settings.chunk_size),
     ));
 }
 
+/// Blocks get their color and material from the palette, so they are redrawn when a palette entry changes.
+fn redraw_chunks_on_palette_change(
+    color_library: Res<ColorLibrary>,
+    mut drawn_palette: Local<Vec<PaletteColor>>,
+    mut chunks: Query<&mut Chunk>,
+) {
+    if drawn_palette.as_slice() == color_library.palette_colors() {
+        return;
+    }
+
+    *drawn_palette = color_library.palette_colors().to_vec();
+
+    for mut chunk in chunks.iter_mut() {
+        chunk.set_changed();
+    }
+}
+
 fn redraw_requested_chunks(
     mut commands: Commands,
     chunks: Query<&Chunk>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     world_settings: Res<WorldSettings>,
+    color_library: Res<ColorLibrary>,
     mut chunk_redraw_requests: EventReader<OnRedrawChunkRequest>,
 ) {
     for request in chunk_redraw_requests.iter() {
@@ -48,6 +80,7 @@ fn redraw_requested_chunks(
                 &mut commands,
                 &mut meshes,
                 &mut materials,
+                &color_library,
                 blocks,
                 chunk_entity,
             );
@@ -59,12 +92,30 @@ fn draw_chunk(
     mut commands: &mut Commands,
     meshes: &mut Assets<Mesh>,
     materials: &mut Assets<StandardMaterial>,
+    color_library: &ColorLibrary,
     blocks: Vec<(Block, Vec3)>,
     chunk_entity: Entity,
 ) {
-    let mesh_handle = meshes.add(shape::Cube::new(0.9).into());
-
-    let block_entities = spawn_blocks(&mut commands, blocks, mesh_handle, materials);
+    let shape_meshes: HashMap<BlockShape, (Handle<Mesh>, Collider)> = BlockShape::ALL
+        .into_iter()
+        .map(|shape| {
+            (
+                shape,
+                (
+                    meshes.add(build_shape_mesh(shape)),
+                    build_shape_collider(shape),
+                ),
+            )
+        })
+        .collect();
+
+    let block_entities = spawn_blocks(
+        &mut commands,
+        blocks,
+        &shape_meshes,
+        materials,
+        color_library,
+    );
 
     commands.entity(chunk_entity).push_children(&block_entities);
 }
@@ -72,19 +123,36 @@ fn draw_chunk(
 fn spawn_blocks(
     commands: &mut Commands,
     blocks: Vec<(Block, Vec3)>,
-    mesh_handle: Handle<Mesh>,
+    shape_meshes: &HashMap<BlockShape, (Handle<Mesh>, Collider)>,
     materials: &mut Assets<StandardMaterial>,
+    color_library: &ColorLibrary,
 ) -> Vec<Entity> {
     let mut spawned_entities = Vec::new();
 
+    // One material per palette entry, shared by all blocks that use it.
+    let palette_materials: Vec<Handle<StandardMaterial>> = color_library
+        .palette_colors()
+        .iter()
+        .map(|palette_color| materials.add(palette_color.to_standard_material()))
+        .collect();
+    let missing_material = materials.add(MISSING_COLOR.into());
+
     for (block, position) in blocks.iter() {
-        let material_handle = materials.add(StandardMaterial {
-            base_color: block.color,
-            ..default()
-        });
+        let material_handle = palette_materials
+            .get(block.palette_index())
+            .unwrap_or(&missing_material);
+
+        let (mesh_handle, collider) = &shape_meshes[&block.shape()];
+        let transform = Transform::from_translation(*position)
+            .with_rotation(block.orientation().rotation());
 
         let id = commands
-            .spawn(build_block(&mesh_handle, &material_handle, *position))
+            .spawn(build_block(
+                mesh_handle,
+                material_handle,
+                collider.clone(),
+                transform,
+            ))
             .id();
 
         spawned_entities.push(id);
@@ -107,15 +175,16 @@ fn calculate_blocks_spawn_positions(
 fn build_block(
     mesh_handle: &Handle<Mesh>,
     material_handle: &Handle<StandardMaterial>,
-    position: Vec3,
+    collider: Collider,
+    transform: Transform,
 ) -> (PbrBundle, Collider) {
     (
         PbrBundle {
             mesh: mesh_handle.clone(),
             material: material_handle.clone(),
-            transform: Transform::from_translation(position),
+            transform,
             ..default()
         },
-        Collider::cuboid(0.5, 0.5, 0.5),
+        collider,
     )
 }
@@ -0,0 +1,260 @@
+use bevy::{
+    prelude::*,
+    render::{mesh::Indices, render_resource::PrimitiveTopology},
+};
+use bevy_rapier3d::prelude::*;
+
+use crate::world::block::block_shape::BlockShape;
+
+/// Blocks are drawn slightly smaller than their collider, so the edges between blocks stay visible.
+const MESH_SCALE: f32 = 0.9;
+const HALF: f32 = 0.5;
+
+/// A flat polygon, its corners wind counter clockwise when seen from outside the shape.
+type Face = Vec<Vec3>;
+
+/// A convex piece of a shape, described by its faces.
+type Part = Vec<Face>;
+
+pub fn build_shape_mesh(shape: BlockShape) -> Mesh {
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+
+    for face in shape_parts(shape).iter().flatten() {
+        let first_index = positions.len() as u32;
+        let normal = face_normal(face);
+
+        for corner in face.iter() {
+            positions.push((*corner * MESH_SCALE).to_array());
+            normals.push(normal.to_array());
+        }
+
+        // Fan triangulation, faces are convex.
+        for corner_index in 1..face.len() as u32 - 1 {
+            indices.extend([
+                first_index,
+                first_index + corner_index,
+                first_index + corner_index + 1,
+            ]);
+        }
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.set_indices(Some(Indices::U32(indices)));
+    mesh
+}
+
+pub fn build_shape_collider(shape: BlockShape) -> Collider {
+    if shape == BlockShape::Full {
+        return Collider::cuboid(HALF, HALF, HALF);
+    }
+
+    let mut colliders: Vec<Collider> = shape_parts(shape)
+        .iter()
+        .filter_map(|part| Collider::convex_hull(&part_corners(part)))
+        .collect();
+
+    if colliders.len() == 1 {
+        return colliders.remove(0);
+    }
+
+    Collider::compound(
+        colliders
+            .into_iter()
+            .map(|collider| (Vec3::ZERO, Quat::IDENTITY, collider))
+            .collect(),
+    )
+}
+
+/// The convex parts of a shape that faces north and isn't upside down, in a cell centered on the origin.
+fn shape_parts(shape: BlockShape) -> Vec<Part> {
+    let bottom = Vec3::splat(-HALF);
+    let top = Vec3::splat(HALF);
+
+    match shape {
+        BlockShape::Full => vec![box_faces(bottom, top)],
+        BlockShape::Slab => vec![box_faces(bottom, Vec3::new(HALF, 0.0, HALF))],
+        BlockShape::Stair => vec![
+            box_faces(bottom, Vec3::new(HALF, 0.0, HALF)),
+            box_faces(Vec3::new(-HALF, 0.0, -HALF), Vec3::new(HALF, HALF, 0.0)),
+        ],
+        BlockShape::Slope => vec![slope_faces()],
+        BlockShape::CornerSlope => vec![corner_slope_faces()],
+    }
+}
+
+fn box_faces(min: Vec3, max: Vec3) -> Part {
+    let corner = |x: f32, y: f32, z: f32| Vec3::new(x, y, z);
+    let (x0, y0, z0) = (min.x, min.y, min.z);
+    let (x1, y1, z1) = (max.x, max.y, max.z);
+
+    vec![
+        vec![
+            corner(x1, y0, z1),
+            corner(x1, y0, z0),
+            corner(x1, y1, z0),
+            corner(x1, y1, z1),
+        ],
+        vec![
+            corner(x0, y0, z0),
+            corner(x0, y0, z1),
+            corner(x0, y1, z1),
+            corner(x0, y1, z0),
+        ],
+        vec![
+            corner(x0, y1, z1),
+            corner(x1, y1, z1),
+            corner(x1, y1, z0),
+            corner(x0, y1, z0),
+        ],
+        vec![
+            corner(x0, y0, z0),
+            corner(x1, y0, z0),
+            corner(x1, y0, z1),
+            corner(x0, y0, z1),
+        ],
+        vec![
+            corner(x0, y0, z1),
+            corner(x1, y0, z1),
+            corner(x1, y1, z1),
+            corner(x0, y1, z1),
+        ],
+        vec![
+            corner(x0, y0, z0),
+            corner(x0, y1, z0),
+            corner(x1, y1, z0),
+            corner(x1, y0, z0),
+        ],
+    ]
+}
+
+fn slope_faces() -> Part {
+    let corner = |x: f32, y: f32, z: f32| Vec3::new(x, y, z) * HALF;
+
+    vec![
+        // Bottom
+        vec![
+            corner(-1.0, -1.0, -1.0),
+            corner(1.0, -1.0, -1.0),
+            corner(1.0, -1.0, 1.0),
+            corner(-1.0, -1.0, 1.0),
+        ],
+        // Back
+        vec![
+            corner(-1.0, -1.0, -1.0),
+            corner(-1.0, 1.0, -1.0),
+            corner(1.0, 1.0, -1.0),
+            corner(1.0, -1.0, -1.0),
+        ],
+        // Ramp
+        vec![
+            corner(-1.0, -1.0, 1.0),
+            corner(1.0, -1.0, 1.0),
+            corner(1.0, 1.0, -1.0),
+            corner(-1.0, 1.0, -1.0),
+        ],
+        // Sides
+        vec![
+            corner(-1.0, -1.0, -1.0),
+            corner(-1.0, -1.0, 1.0),
+            corner(-1.0, 1.0, -1.0),
+        ],
+        vec![
+            corner(1.0, -1.0, -1.0),
+            corner(1.0, 1.0, -1.0),
+            corner(1.0, -1.0, 1.0),
+        ],
+    ]
+}
+
+fn corner_slope_faces() -> Part {
+    let corner = |x: f32, y: f32, z: f32| Vec3::new(x, y, z) * HALF;
+    let peak = corner(-1.0, 1.0, -1.0);
+
+    vec![
+        // Bottom
+        vec![
+            corner(-1.0, -1.0, -1.0),
+            corner(1.0, -1.0, -1.0),
+            corner(1.0, -1.0, 1.0),
+            corner(-1.0, -1.0, 1.0),
+        ],
+        // Back and left
+        vec![corner(-1.0, -1.0, -1.0), peak, corner(1.0, -1.0, -1.0)],
+        vec![corner(-1.0, -1.0, -1.0), corner(-1.0, -1.0, 1.0), peak],
+        // Ramps
+        vec![peak, corner(1.0, -1.0, 1.0), corner(1.0, -1.0, -1.0)],
+        vec![peak, corner(-1.0, -1.0, 1.0), corner(1.0, -1.0, 1.0)],
+    ]
+}
+
+fn face_normal(face: &Face) -> Vec3 {
+    (face[1] - face[0]).cross(face[2] - face[0]).normalize()
+}
+
+fn part_corners(part: &Part) -> Vec<Vec3> {
+    let mut corners: Vec<Vec3> = Vec::new();
+
+    for corner in part.iter().flatten() {
+        if !corners.contains(corner) {
+            corners.push(*corner);
+        }
+    }
+
+    corners
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faces_point_away_from_their_part() {
+        for shape in BlockShape::ALL {
+            for part in shape_parts(shape) {
+                let corners = part_corners(&part);
+                let center = corners.iter().sum::<Vec3>() / corners.len() as f32;
+
+                for face in part.iter() {
+                    let face_center = face.iter().sum::<Vec3>() / face.len() as f32;
+
+                    assert!(
+                        face_normal(face).dot(face_center - center) > 0.0,
+                        "{:?} has an inward face",
+                        shape
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shapes_stay_inside_their_cell() {
+        for shape in BlockShape::ALL {
+            for corner in shape_parts(shape).iter().flatten().flatten() {
+                assert!(corner.abs().max_element() <= HALF);
+            }
+        }
+    }
+
+    #[test]
+    fn slab_mesh_is_half_height() {
+        let mesh = build_shape_mesh(BlockShape::Slab);
+        let aabb = mesh.compute_aabb().unwrap();
+
+        assert_eq!(aabb.max().y, 0.0);
+        assert_eq!(aabb.min().y, -HALF * MESH_SCALE);
+    }
+
+    #[test]
+    fn stair_collider_has_both_steps() {
+        let collider = build_shape_collider(BlockShape::Stair);
+
+        assert!(collider
+            .as_compound()
+            .is_some_and(|compound| compound.shapes().len() == 2));
+    }
+}
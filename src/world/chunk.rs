@@ -35,10 +35,18 @@ impl Chunk {
 
     #[allow(unused)]
     pub fn get_block(&self, coord: Coordinate) -> Option<Block> {
+        if !self.contains_coordinate(coord) {
+            return None;
+        }
+
         self.blocks.get(self.coordinate_to_index(coord)).cloned()?
     }
 
     pub fn set_block(&mut self, coord: Coordinate, new_block: Option<Block>) {
+        if !self.contains_coordinate(coord) {
+            return;
+        }
+
         let index = self.coordinate_to_index(coord);
         if let Some(block) = self.blocks.get_mut(index) {
             *block = new_block;
@@ -56,14 +64,66 @@ impl Chunk {
             .collect()
     }
 
-    pub fn flat_ground(ground_height: usize, color: Color, chunk_size: usize) -> Self {
+    /// Gives the blocks at the given coordinates that use one of the `from` palette entries the `to` entry,
+    /// returns how many blocks changed.
+    pub fn replace_palette_index(
+        &mut self,
+        coordinates: &[Coordinate],
+        from: &[usize],
+        to: usize,
+    ) -> usize {
+        let mut replaced_count = 0;
+
+        for coord in coordinates.iter() {
+            if let Some(block) = self.get_block(*coord) {
+                let index = block.palette_index();
+
+                if index != to && from.contains(&index) {
+                    self.set_block(*coord, Some(block.with_palette_index(to)));
+                    replaced_count += 1;
+                }
+            }
+        }
+
+        replaced_count
+    }
+
+    /// Changes the palette index of every block, used to keep blocks on the same color when the palette is reordered.
+    pub fn remap_palette_indices(&mut self, remap: impl Fn(usize) -> usize) {
+        for block in self.blocks.iter_mut().flatten() {
+            let new_index = remap(block.palette_index());
+
+            if new_index != block.palette_index() {
+                *block = block.with_palette_index(new_index);
+                self.data_changed = true;
+            }
+        }
+    }
+
+    pub fn count_blocks_with_palette_index(&self, palette_index: usize) -> usize {
+        self.blocks
+            .iter()
+            .flatten()
+            .filter(|block| block.palette_index() == palette_index)
+            .count()
+    }
+
+    /// The palette index of every assigned block.
+    pub fn palette_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.blocks
+            .iter()
+            .flatten()
+            .map(|block| block.palette_index())
+    }
+
+    pub fn flat_ground(ground_height: usize, palette_index: usize, chunk_size: usize) -> Self {
         let mut chunk = Chunk::empty(chunk_size);
 
         for x in 0..chunk_size {
             for y in 0..ground_height {
                 for z in 0..chunk_size {
                     let coord = Coordinate::new(x, y, z);
-                    chunk.set_block(coord, Some(Block::new(color)));
+                    chunk.set_block(coord, Some(Block::new(palette_index)));
                 }
             }
         }
@@ -71,6 +131,11 @@ impl Chunk {
         chunk
     }
 
+    /// Returns true if the coordinate lies inside this chunk, coordinates outside would wrap around to other blocks.
+    pub fn contains_coordinate(&self, coord: Coordinate) -> bool {
+        coord.x < self.size && coord.y < self.size && coord.z < self.size
+    }
+
     pub fn coordinate_to_index(&self, coord: Coordinate) -> usize {
         coord.x + coord.y * self.size + coord.z * self.size * self.size
     }
@@ -153,11 +218,23 @@ mod tests {
         assert_eq!(chunk.get_block(coord), test_block());
     }
 
+    #[test]
+    fn ignores_coordinates_outside_chunk() {
+        let mut chunk = Chunk::empty(4);
+        let outside_coord = Coordinate::new(4, 0, 0);
+
+        chunk.set_block(outside_coord, test_block());
+
+        assert_eq!(chunk.get_block(outside_coord), None);
+        assert_eq!(chunk.get_block(Coordinate::new(0, 1, 0)), None);
+        assert!(!chunk.data_changed);
+    }
+
     #[test]
     fn chunk_can_be_created_as_flat_ground() {
         let ground_height = 2;
 
-        let chunk = Chunk::flat_ground(ground_height, Color::WHITE, 4);
+        let chunk = Chunk::flat_ground(ground_height, 0, 4);
 
         let ground_coord = Coordinate::new(0, ground_height - 1, 0);
         let empty_coord = Coordinate::new(0, ground_height, 0);
@@ -177,6 +254,54 @@ mod tests {
         assert_eq!(chunk.data_changed, true);
     }
 
+    #[test]
+    fn can_replace_palette_index_of_blocks() {
+        let mut chunk = Chunk::flat_ground(2, 0, 4);
+        let coordinates: Vec<Coordinate> = chunk
+            .get_assigned_blocks_with_coords()
+            .into_iter()
+            .map(|(_, coord)| coord)
+            .collect();
+
+        let replaced_count = chunk.replace_palette_index(&coordinates, &[0], 2);
+
+        assert_eq!(replaced_count, 32);
+        assert_eq!(
+            chunk.get_block(Coordinate::new(1, 1, 1)),
+            Some(Block::new(2))
+        );
+    }
+
+    #[test]
+    fn replacing_palette_index_skips_other_indices_and_coordinates() {
+        let mut chunk = Chunk::flat_ground(1, 0, 4);
+        chunk.set_block(Coordinate::new(1, 0, 0), Some(Block::new(1)));
+
+        let coordinates = vec![Coordinate::new(0, 0, 0), Coordinate::new(1, 0, 0)];
+        let replaced_count = chunk.replace_palette_index(&coordinates, &[0], 2);
+
+        assert_eq!(replaced_count, 1);
+        assert_eq!(
+            chunk.get_block(Coordinate::new(1, 0, 0)),
+            Some(Block::new(1))
+        );
+        assert_eq!(chunk.get_block(Coordinate::new(2, 0, 0)), test_block());
+    }
+
+    #[test]
+    fn can_remap_and_count_palette_indices() {
+        let mut chunk = Chunk::flat_ground(1, 0, 4);
+        chunk.set_block(Coordinate::new(1, 0, 0), Some(Block::new(1)));
+
+        assert_eq!(chunk.count_blocks_with_palette_index(0), 15);
+        assert_eq!(chunk.count_blocks_with_palette_index(1), 1);
+
+        chunk.remap_palette_indices(|index| 1 - index);
+
+        assert_eq!(chunk.count_blocks_with_palette_index(0), 1);
+        assert_eq!(chunk.count_blocks_with_palette_index(1), 15);
+    }
+
     #[test]
     fn can_calculate_index_from_coordinate() {
         let size = 4;
@@ -208,6 +333,6 @@ mod tests {
     }
 
     fn test_block() -> Option<Block> {
-        Some(Block::new(Color::WHITE))
+        Some(Block::new(0))
     }
 }
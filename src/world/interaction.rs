@@ -1,7 +1,12 @@
 use bevy::prelude::*;
 
 use crate::{
-    player::editor_modes::build::{OnPlaceBlockRequest, OnRemoveBlockRequest},
+    game_systems::color_library::ColorLibrary,
+    player::editor_modes::{
+        build::{OnPlaceBlockRequest, OnRemoveBlockRequest},
+        paint::OnPaintBlockRequest,
+        replace_color::{OnReplaceColorRequest, ReplaceScope},
+    },
     world::chunk::Chunk,
 };
 
@@ -9,7 +14,15 @@ pub struct WorldInteractionPlugin;
 
 impl Plugin for WorldInteractionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (handle_place_request, handle_remove_request));
+        app.add_systems(
+            Update,
+            (
+                handle_place_request,
+                handle_remove_request,
+                handle_paint_request,
+                handle_replace_color_request,
+            ),
+        );
     }
 }
 
@@ -34,3 +47,55 @@ fn handle_remove_request(
         }
     }
 }
+
+fn handle_paint_request(
+    mut paint_events: EventReader<OnPaintBlockRequest>,
+    mut chunks: Query<&mut Chunk>,
+) {
+    for event in paint_events.iter() {
+        for mut chunk in chunks.iter_mut() {
+            let painted_block = chunk
+                .get_block(event.coord)
+                .filter(|block| block.palette_index() != event.palette_index)
+                .map(|block| block.with_palette_index(event.palette_index));
+
+            if painted_block.is_some() {
+                chunk.set_block(event.coord, painted_block);
+            }
+        }
+    }
+}
+
+fn handle_replace_color_request(
+    mut replace_events: EventReader<OnReplaceColorRequest>,
+    mut chunks: Query<&mut Chunk>,
+    color_library: Res<ColorLibrary>,
+) {
+    for event in replace_events.iter() {
+        let mut replaced_count = 0;
+
+        let mut from_indices = color_library
+            .color_at(event.from)
+            .map(|color| color_library.indices_matching(color, event.tolerance))
+            .unwrap_or_default();
+
+        if !from_indices.contains(&event.from) {
+            from_indices.push(event.from);
+        }
+
+        for mut chunk in chunks.iter_mut() {
+            let coordinates = match &event.scope {
+                ReplaceScope::Scene => chunk
+                    .get_assigned_blocks_with_coords()
+                    .into_iter()
+                    .map(|(_, coord)| coord)
+                    .collect(),
+                ReplaceScope::Selection(coordinates) => coordinates.clone(),
+            };
+
+            replaced_count += chunk.replace_palette_index(&coordinates, &from_indices, event.to);
+        }
+
+        println!("Replaced the color of {} blocks", replaced_count);
+    }
+}